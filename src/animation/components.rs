@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// === Data ===
+// Mirrors the *.anim.json files in assets/animations
+
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct SheetData {
+    pub texture: String,
+    pub frame_size: [u32; 2],
+    pub columns: u32,
+    pub rows: u32,
+    // Keyed "<state>_<direction>", e.g. "walk_left"
+    pub clips: HashMap<String, Clip>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Clip {
+    // Indices into the sheet, left to right then top to bottom
+    pub frames: Vec<usize>,
+    // Seconds each frame is shown for
+    pub frame_time: f32,
+    #[serde(default = "looping_default")]
+    pub looping: bool,
+    #[serde(default)]
+    pub flip_x: bool,
+    // Optional per frame nudge of the sprite, in sheet pixels
+    #[serde(default)]
+    pub offsets: Vec<[f32; 2]>,
+}
+
+fn looping_default() -> bool {
+    true
+}
+
+pub struct LoadedSheet {
+    pub data: SheetData,
+    pub texture: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+// Every sheet in assets/animations that has loaded, keyed by file name without the extension
+#[derive(Resource, Default)]
+pub struct AnimationSheets(pub HashMap<String, LoadedSheet>);
+
+impl AnimationSheets {
+    // Sprite & atlas to spawn an animated entity with
+    pub fn sprite(&self, sheet: &str) -> Option<(Handle<Image>, TextureAtlas)> {
+        let loaded = self.0.get(sheet)?;
+        Some((
+            loaded.texture.clone(),
+            TextureAtlas {
+                layout: loaded.layout.clone(),
+                index: 0,
+            },
+        ))
+    }
+}
+
+// Shared clock for everything that animates in lockstep, like water tiles
+// Runs on virtual time so it stops while the game is paused
+#[derive(Resource, Default)]
+pub struct AnimationClock(pub f32);
+
+impl AnimationClock {
+    // Which of `count` frames is showing when each lasts `frame_time` seconds
+    pub fn frame(&self, frame_time: f32, count: usize) -> usize {
+        if count == 0 || frame_time <= 0.0 {
+            return 0;
+        }
+        (self.0 / frame_time) as usize % count
+    }
+}
+
+// === Components ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Walk,
+    Attack,
+    Hurt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Facing {
+    #[default]
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl AnimationState {
+    fn name(&self) -> &'static str {
+        match self {
+            AnimationState::Idle => "idle",
+            AnimationState::Walk => "walk",
+            AnimationState::Attack => "attack",
+            AnimationState::Hurt => "hurt",
+        }
+    }
+}
+
+impl Facing {
+    fn name(&self) -> &'static str {
+        match self {
+            Facing::Down => "down",
+            Facing::Up => "up",
+            Facing::Left => "left",
+            Facing::Right => "right",
+        }
+    }
+
+    pub fn from_direction(direction: Vec2) -> Option<Self> {
+        if direction.length_squared() < f32::EPSILON {
+            None
+        } else if direction.x.abs() > direction.y.abs() {
+            Some(if direction.x > 0.0 {
+                Facing::Right
+            } else {
+                Facing::Left
+            })
+        } else if direction.y > 0.0 {
+            Some(Facing::Up)
+        } else {
+            Some(Facing::Down)
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct SpriteAnimation {
+    pub sheet: String,
+    pub state: AnimationState,
+    pub facing: Facing,
+    // Position within the current clip
+    pub frame: usize,
+    pub elapsed: f32,
+    // Attack & hurt play through once before velocity picks the state again
+    pub one_shot: Option<AnimationState>,
+}
+
+impl SpriteAnimation {
+    pub fn new(sheet: &str) -> Self {
+        Self {
+            sheet: sheet.to_string(),
+            state: AnimationState::Idle,
+            facing: Facing::Down,
+            frame: 0,
+            elapsed: 0.0,
+            one_shot: None,
+        }
+    }
+
+    pub fn clip_name(&self) -> String {
+        format!("{}_{}", self.state.name(), self.facing.name())
+    }
+
+    pub fn play(&mut self, state: AnimationState) {
+        if self.state != state {
+            self.state = state;
+            self.frame = 0;
+            self.elapsed = 0.0;
+        }
+    }
+}
+
+// World units per second, worked out from how far the entity moved last frame
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Velocity(pub Vec2);
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::data::DataFileApp;
+use crate::health::HealthSet;
+
+pub use components::{AnimationClock, AnimationSheets, SpriteAnimation};
+
+// Sprite sheet animation, clips are read from assets/animations/*.anim.json
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<components::AnimationClock>();
+        // Sheets are loaded as a folder, so they're found by this extension rather than their type
+        app.add_data_file::<AnimationSheets>(systems::SHEETS_PATH, &["anim.json"]);
+        app.add_systems(PreUpdate, systems::tick_clock);
+        app.add_systems(
+            Update,
+            (
+                systems::trigger_attack,
+                systems::trigger_hurt.after(HealthSet),
+                systems::pick_state,
+                systems::advance_frames,
+            )
+                .chain(),
+        );
+    }
+}
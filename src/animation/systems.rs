@@ -0,0 +1,172 @@
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::animation::components::*;
+use crate::combat::components::WeaponFired;
+use crate::data::DataRegistry;
+use crate::health::events::DamageEvent;
+
+pub const SHEETS_PATH: &str = "animations";
+// Below this speed an entity counts as standing still
+const WALK_THRESHOLD: f32 = 20.0;
+
+// Sheets are added as they load & rebuilt when their file changes,
+// anything already spawned from a sheet is pointed at the new texture & layout
+impl DataRegistry for AnimationSheets {
+    type Data = SheetData;
+    type Param = (
+        Res<'static, AssetServer>,
+        ResMut<'static, Assets<TextureAtlasLayout>>,
+        Query<
+            'static,
+            'static,
+            (
+                &'static SpriteAnimation,
+                &'static mut Handle<Image>,
+                &'static mut TextureAtlas,
+            ),
+        >,
+    );
+
+    // player.anim.json is the "player" sheet
+    fn reload(
+        &mut self,
+        name: &str,
+        sheet: &SheetData,
+        (asset_server, layouts, sprites): &mut SystemParamItem<Self::Param>,
+    ) {
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::new(sheet.frame_size[0], sheet.frame_size[1]),
+            sheet.columns,
+            sheet.rows,
+            None,
+            None,
+        );
+        let layout = match self.0.get(name) {
+            Some(loaded) => {
+                layouts.insert(&loaded.layout, layout);
+                loaded.layout.clone()
+            }
+            None => layouts.add(layout),
+        };
+        let loaded = LoadedSheet {
+            texture: asset_server.load(sheet.texture.clone()),
+            layout,
+            data: sheet.clone(),
+        };
+
+        for (animation, mut texture, mut atlas) in sprites.iter_mut() {
+            if animation.sheet == name {
+                *texture = loaded.texture.clone();
+                atlas.layout = loaded.layout.clone();
+            }
+        }
+        self.0.insert(name.to_string(), loaded);
+    }
+}
+
+pub fn tick_clock(mut clock: ResMut<AnimationClock>, time: Res<Time>) {
+    clock.0 += time.delta_seconds();
+}
+
+// Firing plays the attack clip facing the way the shot went
+pub fn trigger_attack(mut fired: EventReader<WeaponFired>, mut query: Query<&mut SpriteAnimation>) {
+    for event in fired.read() {
+        let Ok(mut animation) = query.get_mut(event.shooter) else {
+            continue;
+        };
+        if let Some(facing) = Facing::from_direction(event.direction) {
+            animation.facing = facing;
+        }
+        animation.one_shot = Some(AnimationState::Attack);
+        animation.frame = 0;
+        animation.elapsed = 0.0;
+    }
+}
+
+// Taking damage interrupts whatever was playing
+pub fn trigger_hurt(mut damage: EventReader<DamageEvent>, mut query: Query<&mut SpriteAnimation>) {
+    for event in damage.read() {
+        let Ok(mut animation) = query.get_mut(event.target) else {
+            continue;
+        };
+        animation.one_shot = Some(AnimationState::Hurt);
+        animation.frame = 0;
+        animation.elapsed = 0.0;
+    }
+}
+
+pub fn pick_state(mut query: Query<(&mut SpriteAnimation, Option<&Velocity>)>) {
+    for (mut animation, velocity) in query.iter_mut() {
+        let velocity = velocity.map(|v| v.0).unwrap_or(Vec2::ZERO);
+
+        if let Some(state) = animation.one_shot {
+            animation.state = state;
+            continue;
+        }
+        if velocity.length() > WALK_THRESHOLD {
+            if let Some(facing) = Facing::from_direction(velocity) {
+                if animation.facing != facing {
+                    animation.facing = facing;
+                    animation.frame = 0;
+                }
+            }
+            animation.play(AnimationState::Walk);
+        } else {
+            animation.play(AnimationState::Idle);
+        }
+    }
+}
+
+// Steps through clips on elapsed time rather than frames drawn
+pub fn advance_frames(
+    sheets: Res<AnimationSheets>,
+    time: Res<Time>,
+    mut query: Query<(&mut SpriteAnimation, &mut TextureAtlas, &mut Sprite)>,
+) {
+    for (mut animation, mut atlas, mut sprite) in query.iter_mut() {
+        let Some(sheet) = sheets.0.get(&animation.sheet) else {
+            continue;
+        };
+        let Some(clip) = sheet.data.clips.get(&animation.clip_name()) else {
+            continue;
+        };
+        if clip.frames.is_empty() {
+            continue;
+        }
+
+        animation.elapsed += time.delta_seconds();
+        while clip.frame_time > 0.0 && animation.elapsed >= clip.frame_time {
+            animation.elapsed -= clip.frame_time;
+            if animation.frame + 1 < clip.frames.len() {
+                animation.frame += 1;
+            } else if clip.looping {
+                animation.frame = 0;
+            } else {
+                // One shot finished, hand back to the velocity driven state
+                animation.one_shot = None;
+                animation.elapsed = 0.0;
+                break;
+            }
+        }
+        let frame = animation.frame.min(clip.frames.len() - 1);
+
+        if atlas.index != clip.frames[frame] {
+            atlas.index = clip.frames[frame];
+        }
+        if sprite.flip_x != clip.flip_x {
+            sprite.flip_x = clip.flip_x;
+        }
+        // Offsets move the anchor so the entity's transform is left alone
+        let offset = clip.offsets.get(frame).copied().unwrap_or([0.0, 0.0]);
+        let size = Vec2::new(
+            sheet.data.frame_size[0] as f32,
+            sheet.data.frame_size[1] as f32,
+        );
+        let anchor = Anchor::Custom(-Vec2::new(offset[0], offset[1]) / size);
+        if sprite.anchor != anchor {
+            sprite.anchor = anchor;
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::items::ItemDefinition;
+use crate::procedural_generation::map::Coord;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Building {
+    // Id of the item it was placed from
+    pub item: String,
+    // Tile within the chunk
+    pub column: usize,
+    pub row: usize,
+    // Quarter turns clockwise
+    #[serde(default)]
+    pub rotation: u8,
+    // Doors only, an open door can be walked & shot through
+    #[serde(default)]
+    pub open: bool,
+}
+
+impl Building {
+    // Upright buildings stop the player & projectiles, unless they're an open door
+    pub fn is_solid(&self, item: &ItemDefinition) -> bool {
+        item.layer == BuildingLayer::Upright && !(item.door && self.open)
+    }
+}
+
+// Floors lie flat under everything else, so a tile can hold one floor & one upright building
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BuildingLayer {
+    Floor,
+    #[default]
+    Upright,
+}
+
+// Sent when a chunk's buildings change, so its sprites are rebuilt
+#[derive(Event, Debug, Clone)]
+pub struct BuildingsChanged(pub Coord);
+
+// A tile the selected item would be built on
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub chunk: Coord,
+    pub column: usize,
+    pub row: usize,
+    pub valid: bool,
+}
+
+// Translucent preview of the selected placeable item, snapped to the tile under the cursor
+#[derive(Component, Debug, Default)]
+pub struct BuildingGhost {
+    pub rotation: u8,
+    pub placement: Option<Placement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::items::{ItemList, ItemRegistry};
+
+    #[test]
+    fn walls_and_shut_doors_are_solid() {
+        let list: ItemList = serde_json::from_str(include_str!("../../assets/items.json")).unwrap();
+        let items = ItemRegistry::from_definitions(list.0);
+        let solid = |item: &str, open| {
+            let building = Building {
+                item: item.to_string(),
+                column: 0,
+                row: 0,
+                rotation: 0,
+                open,
+            };
+            building.is_solid(items.get(item).unwrap())
+        };
+        assert!(solid("wall", false));
+        assert!(solid("door", false));
+        assert!(!solid("door", true));
+        assert!(!solid("floor", false));
+        // Only doors open
+        assert!(solid("wall", true));
+    }
+}
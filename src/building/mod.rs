@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::crafting::CraftingScreen;
+use crate::dungeon::Location;
+use crate::inventory::InventoryScreen;
+use crate::player::systems::movement::movement_system;
+use crate::procedural_generation::chunk_sprites::ChunkSpritesPlugin;
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+pub use components::{Building, BuildingLayer};
+
+// Placing items like walls & workbenches into the world
+// Buildings are stored in their Chunk & spawned & despawned along with it
+pub struct BuildingPlugin;
+
+impl Plugin for BuildingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ChunkSpritesPlugin::<components::BuildingsChanged>::default());
+        app.add_systems(OnEnter(InGame), systems::spawn_ghost);
+        app.add_systems(OnExit(InGame), systems::despawn_ghost);
+        app.add_systems(
+            Update,
+            (
+                systems::rotate_ghost,
+                systems::update_ghost,
+                systems::place_building,
+            )
+                .chain()
+                .run_if(in_state(InGame))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+        // The ghost stops updating while a screen is open, so it is hidden instead
+        app.add_systems(OnEnter(MapScreen::Open), systems::hide_ghost);
+        app.add_systems(OnEnter(InventoryScreen::Open), systems::hide_ghost);
+        app.add_systems(OnEnter(CraftingScreen::Open), systems::hide_ghost);
+        app.add_systems(
+            Update,
+            systems::keep_out_of_buildings
+                .after(movement_system)
+                .run_if(in_state(InGame))
+                .run_if(in_state(Location::Overworld)),
+        );
+        app.add_systems(
+            Update,
+            systems::toggle_door
+                .run_if(in_state(InGame))
+                .run_if(in_state(Location::Overworld))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+    }
+}
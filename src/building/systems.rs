@@ -0,0 +1,358 @@
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::building::components::*;
+use crate::camera::{cursor_world_position, Camera};
+use crate::depth::{Layer, YSort};
+use crate::dungeon::systems::teleport;
+use crate::inventory::items::{ItemCategory, ItemDefinition, ItemRegistry};
+use crate::inventory::Inventory;
+use crate::player::components::Player;
+use crate::procedural_generation::chunk::{Chunk, TileType};
+use crate::procedural_generation::chunk_sprites::{ChunkSprites, SpriteSpawner};
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::props::prop_on_tile;
+use crate::procedural_generation::tile_map::{TileView, Tiles};
+
+// Furthest from the player a building can be placed
+const PLACE_RANGE: f32 = 200.0;
+// How close the player has to be to a door to open or shut it
+const DOOR_RANGE: f32 = 100.0;
+// Item icons are 16px, this makes them a tile wide
+const BUILDING_SCALE: f32 = TILE_WIDTH / 16.0;
+const GHOST_VALID: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+const GHOST_INVALID: Color = Color::srgba(1.0, 0.25, 0.25, 0.6);
+
+// Not a system
+fn building_rotation(rotation: u8) -> Quat {
+    Quat::from_rotation_z(-(rotation as f32) * FRAC_PI_2)
+}
+
+pub fn spawn_ghost(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: GHOST_VALID,
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, Layer::Overhead.z()))
+                .with_scale(Vec3::splat(BUILDING_SCALE)),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        BuildingGhost::default(),
+    ));
+}
+
+pub fn despawn_ghost(mut commands: Commands, query: Query<Entity, With<BuildingGhost>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn hide_ghost(mut query: Query<(&mut BuildingGhost, &mut Visibility)>) {
+    for (mut ghost, mut visibility) in query.iter_mut() {
+        ghost.placement = None;
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// R turns the next building a quarter turn clockwise
+pub fn rotate_ghost(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut BuildingGhost>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    for mut ghost in query.iter_mut() {
+        ghost.rotation = (ghost.rotation + 1) % 4;
+    }
+}
+
+// Not a system
+// Buildings need a loaded, walkable tile in reach with no prop & nothing else on the same layer
+fn check_placement(
+    rendered: &RenderedChunks,
+    items: &ItemRegistry,
+    item: &ItemDefinition,
+    target: Vec2,
+    player: Vec2,
+) -> Option<Placement> {
+    let (chunk, column, row) = tile_in_chunk(tile_at(target));
+    let data = rendered.chunks.get(&chunk)?;
+    let tile_type = &data.tiles[row][column].tile_type;
+
+    let centre = tile_position(&chunk, column, row);
+    let on_prop = prop_on_tile(data, &chunk, column, row);
+    let planted = data
+        .crops
+        .iter()
+        .any(|crop| crop.column == column && crop.row == row);
+    let occupied = data.buildings.iter().any(|building| {
+        building.column == column
+            && building.row == row
+            && items
+                .get(&building.item)
+                .is_none_or(|other| other.layer == item.layer)
+    });
+
+    let valid = centre.distance(player) <= PLACE_RANGE
+        && !tile_type.is_blocking()
+        && *tile_type != TileType::Water
+        && !on_prop
+        && !planted
+        && !occupied;
+    Some(Placement {
+        chunk,
+        column,
+        row,
+        valid,
+    })
+}
+
+type GhostParts<'a> = (
+    &'a mut BuildingGhost,
+    &'a mut Transform,
+    &'a mut Sprite,
+    &'a mut Handle<Image>,
+    &'a mut Visibility,
+);
+
+// Follows the cursor while a placeable item is selected, red where it can't be built
+pub fn update_ghost(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&bevy::render::camera::Camera, &GlobalTransform), With<Camera>>,
+    player_query: Query<(&Transform, &Inventory), With<Player>>,
+    mut ghost_query: Query<GhostParts, Without<Player>>,
+    items: Res<ItemRegistry>,
+    rendered: Res<RenderedChunks>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok((mut ghost, mut transform, mut sprite, mut texture, mut visibility)) =
+        ghost_query.get_single_mut()
+    else {
+        return;
+    };
+    let cursor = window_query.get_single().ok().and_then(|window| {
+        let (camera, camera_transform) = camera_query.get_single().ok()?;
+        cursor_world_position(window, camera, camera_transform)
+    });
+    let selected = player_query
+        .get_single()
+        .ok()
+        .and_then(|(player, inventory)| {
+            let item = inventory
+                .selected_stack()
+                .and_then(|stack| items.get(&stack.item))
+                .filter(|item| item.category == ItemCategory::Placeable)?;
+            Some((player.translation.truncate(), item))
+        });
+
+    let placement = match (selected, cursor) {
+        (Some((player, item)), Some(cursor)) => {
+            let icon = asset_server.load(item.icon.clone());
+            if *texture != icon {
+                *texture = icon;
+            }
+            check_placement(&rendered, &items, item, cursor, player)
+        }
+        _ => None,
+    };
+
+    match &placement {
+        Some(placement) => {
+            let position = tile_position(&placement.chunk, placement.column, placement.row);
+            transform.translation = position.extend(Layer::Overhead.z());
+            transform.rotation = building_rotation(ghost.rotation);
+            let color = if placement.valid {
+                GHOST_VALID
+            } else {
+                GHOST_INVALID
+            };
+            if sprite.color != color {
+                sprite.color = color;
+            }
+            if *visibility != Visibility::Inherited {
+                *visibility = Visibility::Inherited;
+            }
+        }
+        None => {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+    ghost.placement = placement;
+}
+
+// Right click builds the selected item where the ghost is, using up one of it
+pub fn place_building(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    ghost_query: Query<&BuildingGhost>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    mut rendered: ResMut<RenderedChunks>,
+    mut changed: EventWriter<BuildingsChanged>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Ok(ghost) = ghost_query.get_single() else {
+        return;
+    };
+    let Some(placement) = ghost.placement.as_ref().filter(|placement| placement.valid) else {
+        return;
+    };
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(chunk) = rendered.chunks.get_mut(&placement.chunk) else {
+        return;
+    };
+    let Some(item) = inventory.consume_selected() else {
+        return;
+    };
+
+    chunk.buildings.push(Building {
+        item,
+        column: placement.column,
+        row: placement.row,
+        rotation: ghost.rotation,
+        open: false,
+    });
+    changed.send(BuildingsChanged(placement.chunk.clone()));
+}
+
+impl ChunkSprites for BuildingsChanged {
+    type Param = (Res<'static, ItemRegistry>, Res<'static, AssetServer>);
+
+    fn chunk(&self) -> &Coord {
+        &self.0
+    }
+
+    fn spawn(
+        sprites: &mut SpriteSpawner,
+        coord: &Coord,
+        chunk: &Chunk,
+        (items, asset_server): &mut SystemParamItem<Self::Param>,
+    ) {
+        for building in chunk.buildings.iter() {
+            let Some(item) = items.get(&building.item) else {
+                continue;
+            };
+            let position = tile_position(coord, building.column, building.row);
+            // Open doors are swung a quarter turn out of the doorway
+            let rotation = building.rotation + u8::from(building.open);
+            // Floors sit just above the ground tiles, everything else is sorted with the actors
+            let z = match item.layer {
+                BuildingLayer::Floor => Layer::Ground.z() + 0.5,
+                BuildingLayer::Upright => Layer::Actors.z(),
+            };
+            let mut entity = sprites.spawn(SpriteBundle {
+                texture: asset_server.load(item.icon.clone()),
+                transform: Transform::from_translation(position.extend(z))
+                    .with_rotation(building_rotation(rotation))
+                    .with_scale(Vec3::splat(BUILDING_SCALE)),
+                ..Default::default()
+            });
+            if item.layer == BuildingLayer::Upright {
+                entity.insert(YSort::new(Layer::Actors, TILE_HEIGHT / 2.0));
+            }
+            if let Some(station) = item.station {
+                entity.insert(station);
+            }
+        }
+    }
+}
+
+// Not a system
+// Whether a solid building stands on the tile, used for collisions with the player & projectiles
+pub fn building_blocks(tiles: Tiles, items: &ItemRegistry, tile: IVec2) -> bool {
+    let (coord, column, row) = tile_in_chunk(tile);
+    tiles.chunk(&coord).is_some_and(|chunk| {
+        chunk.buildings.iter().any(|building| {
+            building.column == column
+                && building.row == row
+                && items
+                    .get(&building.item)
+                    .is_some_and(|item| building.is_solid(item))
+        })
+    })
+}
+
+// Walls & shut doors are solid, a blocked move slides along them
+// Anything built on top of the player doesn't trap them, they can always walk off it
+pub fn keep_out_of_buildings(
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    tile_view: TileView,
+    items: Res<ItemRegistry>,
+    mut last_position: Local<Option<Vec2>>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+    let position = player.translation.truncate();
+    let previous = last_position.unwrap_or(position);
+    let blocked = |point: Vec2| building_blocks(tile_view.tiles(), &items, tile_at(point));
+    if blocked(previous) {
+        *last_position = Some(position);
+        return;
+    }
+    let allowed = [
+        position,
+        Vec2::new(position.x, previous.y),
+        Vec2::new(previous.x, position.y),
+    ]
+    .into_iter()
+    .find(|point| !blocked(*point))
+    .unwrap_or(previous);
+
+    if allowed != position {
+        teleport(&mut player, camera_query.get_single_mut().ok(), allowed);
+    }
+    *last_position = Some(allowed);
+}
+
+// F beside a door opens it, or shuts it again
+pub fn toggle_door(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut rendered: ResMut<RenderedChunks>,
+    items: Res<ItemRegistry>,
+    mut changed: EventWriter<BuildingsChanged>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player = player_transform.translation.truncate();
+
+    let mut nearest: Option<(Coord, usize, f32)> = None;
+    for (coord, chunk) in rendered.chunks.iter() {
+        for (index, building) in chunk.buildings.iter().enumerate() {
+            if !items.get(&building.item).is_some_and(|item| item.door) {
+                continue;
+            }
+            let distance = tile_position(coord, building.column, building.row).distance(player);
+            if distance <= DOOR_RANGE && nearest.as_ref().is_none_or(|(_, _, d)| distance < *d) {
+                nearest = Some((coord.clone(), index, distance));
+            }
+        }
+    }
+    let Some((coord, index, _)) = nearest else {
+        return;
+    };
+    if let Some(door) = rendered
+        .chunks
+        .get_mut(&coord)
+        .and_then(|chunk| chunk.buildings.get_mut(index))
+    {
+        door.open = !door.open;
+        changed.send(BuildingsChanged(coord));
+    }
+}
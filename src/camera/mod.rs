@@ -1,3 +1,4 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
@@ -28,3 +29,37 @@ fn camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindo
 
 #[derive(Component)]
 pub struct Camera;
+
+// Projects the mouse cursor through the camera into world space
+// Returns None when the cursor is outside the window
+pub fn cursor_world_position(
+    window: &Window,
+    camera: &bevy::render::camera::Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(camera_transform, cursor)
+}
+
+// The window & camera queries cursor_world_position needs, for systems that only want the point
+#[derive(SystemParam)]
+pub struct CursorWorld<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<
+        'w,
+        's,
+        (
+            &'static bevy::render::camera::Camera,
+            &'static GlobalTransform,
+        ),
+        With<Camera>,
+    >,
+}
+
+impl<'w, 's> CursorWorld<'w, 's> {
+    pub fn position(&self) -> Option<Vec2> {
+        let window = self.windows.get_single().ok()?;
+        let (camera, camera_transform) = self.cameras.get_single().ok()?;
+        cursor_world_position(window, camera, camera_transform)
+    }
+}
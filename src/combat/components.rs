@@ -0,0 +1,38 @@
+// Components & events for projectiles fired by ranged weapons
+
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Projectile {
+    pub velocity: Vec2,
+    pub damage: f32,
+    // Despawns the projectile when it runs out
+    pub lifetime: Timer,
+    // How much further the projectile may travel before it despawns
+    pub range: f32,
+    // Remaining enemies the projectile can pass through
+    pub pierce: u32,
+    pub homing: Option<f32>,
+    pub radius: f32,
+    pub owner: Entity,
+    // Tile the shot was fired from, stone can be stood on so it never stops a shot leaving it
+    pub fired_from: IVec2,
+    // Enemies already hit, so a piercing shot never hits the same enemy twice
+    pub hit: Vec<Entity>,
+}
+
+// Sent whenever a projectile hits an enemy
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ProjectileHit {
+    pub projectile: Entity,
+    pub target: Entity,
+    pub owner: Entity,
+    pub damage: f32,
+}
+
+// Sent whenever a weapon fires, before the projectile exists
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WeaponFired {
+    pub shooter: Entity,
+    pub direction: Vec2,
+}
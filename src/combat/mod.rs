@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::crafting::CraftingScreen;
+use crate::health::HealthSet;
+use crate::inventory::InventoryScreen;
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<components::ProjectileHit>();
+        app.add_event::<components::WeaponFired>();
+        app.add_systems(
+            Update,
+            (
+                systems::firing::fire_weapon
+                    .run_if(in_state(MapScreen::Closed))
+                    .run_if(in_state(InventoryScreen::Closed))
+                    .run_if(in_state(CraftingScreen::Closed)),
+                systems::projectiles::steer_homing,
+                systems::projectiles::move_projectiles,
+                systems::projectiles::enemy_collisions,
+                systems::projectiles::tile_collisions,
+                systems::projectiles::projectile_damage,
+            )
+                .chain()
+                .before(HealthSet)
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(OnExit(InGame), systems::projectiles::despawn_projectiles);
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::{thread_rng, Rng};
+
+use crate::camera::{cursor_world_position, Camera};
+use crate::combat::components::*;
+use crate::depth::Layer;
+use crate::player::components::*;
+use crate::procedural_generation::map::tile_at;
+
+pub fn fire_weapon(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&bevy::render::camera::Camera, &GlobalTransform), With<Camera>>,
+    mut player_query: Query<(Entity, &Transform, &Weapon, &mut FireCooldown), With<Player>>,
+    time: Res<Time>,
+    mut fired: EventWriter<WeaponFired>,
+) {
+    let Ok((player, player_transform, weapon, mut cooldown)) = player_query.get_single_mut() else {
+        return;
+    };
+    cooldown.0 = (cooldown.0 - time.delta_seconds()).max(0.0);
+
+    let Some(spec) = weapon.projectile else {
+        return;
+    };
+    if !mouse_input.pressed(MouseButton::Left) || cooldown.0 > 0.0 || weapon.fire_rate <= 0.0 {
+        return;
+    }
+
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let Some(target) = cursor_world_position(window, camera, camera_transform) else {
+        return;
+    };
+
+    let origin = player_transform.translation.truncate();
+    let aim = (target - origin).normalize_or_zero();
+    if aim == Vec2::ZERO {
+        return;
+    }
+
+    // Randomly rotate the shot within the weapon's spread cone
+    let half_spread = spec.spread / 2.0;
+    let angle = if half_spread > 0.0 {
+        thread_rng().gen_range(-half_spread..=half_spread)
+    } else {
+        0.0
+    };
+    let direction = Vec2::from_angle(angle).rotate(aim);
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(1.0, 0.85, 0.4),
+                custom_size: Some(Vec2::splat(spec.radius * 2.0)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(origin.extend(Layer::Overhead.z()))
+                .with_rotation(Quat::from_rotation_z(direction.to_angle())),
+            ..Default::default()
+        },
+        Projectile {
+            velocity: direction * spec.speed,
+            damage: weapon.damage,
+            lifetime: Timer::from_seconds(spec.lifetime, TimerMode::Once),
+            range: weapon.range,
+            pierce: spec.pierce,
+            homing: spec.homing,
+            radius: spec.radius,
+            owner: player,
+            fired_from: tile_at(origin),
+            hit: Vec::new(),
+        },
+    ));
+
+    fired.send(WeaponFired {
+        shooter: player,
+        direction,
+    });
+    cooldown.0 = 1.0 / weapon.fire_rate;
+}
@@ -0,0 +1,2 @@
+pub mod firing;
+pub mod projectiles;
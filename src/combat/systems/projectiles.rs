@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+
+use crate::building::systems::building_blocks;
+use crate::combat::components::*;
+use crate::dungeon::components::{CurrentDungeon, DUNGEON_ORIGIN};
+use crate::enemy::components::Enemy;
+use crate::health::events::{DamageEvent, DamageType};
+use crate::inventory::ItemRegistry;
+use crate::procedural_generation::tile_map::{trace, TileView};
+
+// How far a homing projectile looks for a target
+const HOMING_RADIUS: f32 = 500.0;
+
+pub fn steer_homing(
+    mut projectile_query: Query<(&mut Projectile, &Transform)>,
+    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    time: Res<Time>,
+) {
+    for (mut projectile, transform) in projectile_query.iter_mut() {
+        let Some(turn_rate) = projectile.homing else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+
+        // Head for the closest enemy that hasn't already been hit
+        let target = enemy_query
+            .iter()
+            .filter(|(entity, _)| !projectile.hit.contains(entity))
+            .map(|(_, enemy)| enemy.translation.truncate())
+            .filter(|enemy| enemy.distance(position) < HOMING_RADIUS)
+            .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+
+        if let Some(target) = target {
+            let desired = target - position;
+            let angle = projectile.velocity.angle_between(desired);
+            let max_turn = turn_rate * time.delta_seconds();
+            let turn = angle.clamp(-max_turn, max_turn);
+            projectile.velocity = Vec2::from_angle(turn).rotate(projectile.velocity);
+        }
+    }
+}
+
+pub fn move_projectiles(
+    mut commands: Commands,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut projectile, mut transform) in projectile_query.iter_mut() {
+        let step = projectile.velocity * time.delta_seconds();
+        transform.translation += step.extend(0.0);
+        transform.rotation = Quat::from_rotation_z(projectile.velocity.to_angle());
+
+        projectile.range -= step.length();
+        projectile.lifetime.tick(time.delta());
+        if projectile.lifetime.finished() || projectile.range <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn enemy_collisions(
+    mut commands: Commands,
+    mut projectile_query: Query<(Entity, &mut Projectile, &Transform)>,
+    enemy_query: Query<(Entity, &Transform, &Enemy)>,
+    mut hits: EventWriter<ProjectileHit>,
+) {
+    for (entity, mut projectile, transform) in projectile_query.iter_mut() {
+        let position = transform.translation.truncate();
+        for (enemy_entity, enemy_transform, enemy) in enemy_query.iter() {
+            if projectile.hit.contains(&enemy_entity) {
+                continue;
+            }
+            let distance = enemy_transform.translation.truncate().distance(position);
+            if distance > enemy.radius + projectile.radius {
+                continue;
+            }
+
+            hits.send(ProjectileHit {
+                projectile: entity,
+                target: enemy_entity,
+                owner: projectile.owner,
+                damage: projectile.damage,
+            });
+            projectile.hit.push(enemy_entity);
+
+            if projectile.pierce == 0 {
+                commands.entity(entity).despawn();
+                break;
+            }
+            projectile.pierce -= 1;
+        }
+    }
+}
+
+// Casts along this frame's step so fast projectiles can't skip over a tile
+// Stone & solid buildings like walls & shut doors stop them, down in a dungeon its walls do
+// The tile a shot was fired from is skipped, or a shot fired standing on stone would stop straight away
+pub fn tile_collisions(
+    mut commands: Commands,
+    projectile_query: Query<(Entity, &Projectile, &Transform)>,
+    tile_map: TileView,
+    dungeon: Option<Res<CurrentDungeon>>,
+    items: Res<ItemRegistry>,
+    time: Res<Time>,
+) {
+    for (entity, projectile, transform) in projectile_query.iter() {
+        let position = transform.translation.truncate();
+        let previous = position - projectile.velocity * time.delta_seconds();
+        let blocked = match dungeon.as_deref() {
+            // Dungeon tiles are laid out from DUNGEON_ORIGIN, not on the overworld's grid
+            Some(dungeon) => trace(
+                previous - DUNGEON_ORIGIN,
+                position - DUNGEON_ORIGIN,
+                |tile| !dungeon.layout.tile(tile).is_walkable(),
+            )
+            .is_some(),
+            None => {
+                let tiles = tile_map.tiles();
+                tiles
+                    .raycast(previous, position, |tile, tile_type| {
+                        tile != projectile.fired_from
+                            && (tile_type.is_blocking() || building_blocks(tiles, &items, tile))
+                    })
+                    .is_some()
+            }
+        };
+        if blocked {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn projectile_damage(
+    mut hits: EventReader<ProjectileHit>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    for hit in hits.read() {
+        damage.send(DamageEvent {
+            target: hit.target,
+            amount: hit.damage,
+            damage_type: DamageType::Physical,
+            source: Some(hit.owner),
+        });
+    }
+}
+
+pub fn despawn_projectiles(mut commands: Commands, query: Query<Entity, With<Projectile>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::inventory::ItemStack;
+
+// Placed buildings that unlock recipes while the player stands near them
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Station {
+    Workbench,
+    Furnace,
+}
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CraftingScreen {
+    #[default]
+    Closed,
+    Open,
+}
+
+// Stations within reach of the player this frame
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct NearbyStations(pub HashSet<Station>);
+
+// The recipe being made, its inputs are taken when it starts
+// Saved with the game so quitting mid craft doesn't lose them
+#[derive(Resource, Debug, Default)]
+pub struct Crafting {
+    pub current: Option<ActiveCraft>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveCraft {
+    // Recipe id, indices can change when recipes.json is edited
+    pub recipe: String,
+    pub elapsed: f32,
+    // What was taken to start it, given back if the recipe is removed from recipes.json
+    #[serde(default)]
+    pub inputs: Vec<ItemStack>,
+}
+
+// === UI ===
+
+#[derive(Component)]
+pub struct CraftingPanel;
+
+#[derive(Component)]
+pub struct RecipeList;
+
+#[derive(Component)]
+pub struct CraftingProgress;
+
+// Starts RecipeRegistry::recipes[index] when clicked
+#[derive(Component)]
+pub struct RecipeButton(pub usize);
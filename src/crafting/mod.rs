@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod recipes;
+mod styles;
+pub mod systems;
+
+use crate::data::DataFileApp;
+use crate::saves::SaveSet;
+use crate::states::AppState::*;
+
+pub use components::{CraftingScreen, Station};
+pub use recipes::RecipeRegistry;
+
+// Crafting from assets/recipes.json, opened with C
+// Some recipes need the player to be stood near a placed station
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CraftingScreen>();
+        app.init_resource::<components::NearbyStations>();
+        app.init_resource::<components::Crafting>();
+        app.add_data_file::<RecipeRegistry>(recipes::RECIPES_PATH, &["json"]);
+        app.add_systems(PreUpdate, recipes::recheck_recipes);
+        app.add_systems(Startup, systems::saving::load_crafting);
+        app.add_systems(OnExit(InGame), systems::layout::force_close);
+        app.add_systems(
+            Update,
+            (
+                systems::crafting::find_nearby_stations,
+                systems::layout::toggle_crafting,
+                systems::crafting::start_crafting.run_if(in_state(CraftingScreen::Open)),
+                systems::crafting::progress_crafting,
+                systems::layout::list_recipes.run_if(in_state(CraftingScreen::Open)),
+                systems::layout::update_progress.run_if(in_state(CraftingScreen::Open)),
+            )
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(
+            OnEnter(CraftingScreen::Open),
+            systems::layout::open_crafting,
+        );
+        app.add_systems(
+            OnExit(CraftingScreen::Open),
+            systems::layout::close_crafting,
+        );
+        app.add_systems(
+            Last,
+            systems::saving::save_crafting.in_set(SaveSet::Collect),
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::crafting::components::Station;
+use crate::data::DataRegistry;
+use crate::inventory::items::ItemRegistry;
+use crate::inventory::{Inventory, ItemStack};
+
+pub const RECIPES_PATH: &str = "recipes.json";
+
+// One entry of assets/recipes.json
+#[derive(Deserialize, Debug, Clone)]
+pub struct Recipe {
+    pub id: String,
+    pub inputs: Vec<ItemStack>,
+    pub outputs: Vec<ItemStack>,
+    // None can be crafted anywhere
+    #[serde(default)]
+    pub station: Option<Station>,
+    // Seconds to craft
+    pub time: f32,
+}
+
+impl Recipe {
+    pub fn affordable(&self, inventory: &Inventory) -> bool {
+        self.inputs
+            .iter()
+            .all(|input| inventory.count(&input.item) >= input.count)
+    }
+}
+
+// The whole of assets/recipes.json
+#[derive(Asset, TypePath, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct RecipeList(pub Vec<Recipe>);
+
+#[derive(Resource, Debug, Default)]
+pub struct RecipeRegistry {
+    pub recipes: Vec<Recipe>,
+}
+
+impl RecipeRegistry {
+    pub fn get(&self, id: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|recipe| recipe.id == id)
+    }
+
+    // Drops every recipe that uses an item the registry doesn't know, returning what was wrong
+    pub fn validate(&mut self, items: &ItemRegistry) -> Vec<String> {
+        let mut errors = Vec::new();
+        self.recipes.retain(|recipe| {
+            let unknown: Vec<&str> = recipe
+                .inputs
+                .iter()
+                .chain(recipe.outputs.iter())
+                .filter(|stack| items.get(&stack.item).is_none())
+                .map(|stack| stack.item.as_str())
+                .collect();
+            if unknown.is_empty() {
+                return true;
+            }
+            errors.push(format!(
+                "recipe {} uses unknown items: {}",
+                recipe.id,
+                unknown.join(", ")
+            ));
+            false
+        });
+        errors
+    }
+}
+
+// Every recipe is checked against the items it was loaded alongside
+impl DataRegistry for RecipeRegistry {
+    type Data = RecipeList;
+    type Param = Res<'static, ItemRegistry>;
+
+    fn reload(&mut self, _name: &str, list: &RecipeList, items: &mut Res<ItemRegistry>) {
+        self.recipes.clear();
+        // Checked again once items.json has loaded
+        if items.0.is_empty() {
+            return;
+        }
+        self.recipes = list.0.clone();
+        for error in self.validate(items) {
+            error!("{RECIPES_PATH}: {error}");
+        }
+    }
+}
+
+// Reads recipes.json again whenever the items change, so they're checked against the new ones
+pub fn recheck_recipes(items: Res<ItemRegistry>, asset_server: Res<AssetServer>) {
+    if items.is_changed() && !items.is_added() {
+        asset_server.reload(RECIPES_PATH);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::items::ItemList;
+
+    fn items() -> ItemRegistry {
+        let list: ItemList = serde_json::from_str(include_str!("../../assets/items.json")).unwrap();
+        ItemRegistry::from_definitions(list.0)
+    }
+
+    fn stack(item: &str) -> ItemStack {
+        ItemStack {
+            item: item.to_string(),
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn shipped_recipes_only_use_known_items() {
+        let list: RecipeList =
+            serde_json::from_str(include_str!("../../assets/recipes.json")).unwrap();
+        let mut registry = RecipeRegistry { recipes: list.0 };
+        let count = registry.recipes.len();
+        assert_eq!(registry.validate(&items()), Vec::<String>::new());
+        assert_eq!(registry.recipes.len(), count);
+    }
+
+    #[test]
+    fn recipes_with_unknown_items_are_dropped() {
+        let recipe = |id: &str, input: &str, output: &str| Recipe {
+            id: id.to_string(),
+            inputs: vec![stack(input)],
+            outputs: vec![stack(output)],
+            station: None,
+            time: 1.0,
+        };
+        let mut registry = RecipeRegistry {
+            recipes: vec![
+                recipe("rope", "fibre", "rope"),
+                recipe("mithril_bar", "mithril_ore", "mithril_bar"),
+            ],
+        };
+        let errors = registry.validate(&items());
+        assert_eq!(
+            errors,
+            vec!["recipe mithril_bar uses unknown items: mithril_ore, mithril_bar".to_string()]
+        );
+        assert_eq!(registry.recipes.len(), 1);
+        assert!(registry.get("rope").is_some());
+    }
+}
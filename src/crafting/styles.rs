@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+pub const PANEL_WIDTH: f32 = 320.0;
+pub const PANEL_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+pub const RECIPE_COLOR: Color = Color::srgba(0.15, 0.15, 0.18, 0.9);
+pub const RECIPE_HOVER_COLOR: Color = Color::srgba(0.3, 0.3, 0.35, 0.9);
+// Recipes the player is missing items for
+pub const MISSING_COLOR: Color = Color::srgba(0.4, 0.4, 0.4, 1.0);
+pub const ICON_SIZE: f32 = 32.0;
+pub const TEXT_SIZE: f32 = 16.0;
+pub const FONT: &str = "fonts/FiraMono-Medium.ttf";
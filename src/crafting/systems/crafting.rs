@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use crate::crafting::components::*;
+use crate::crafting::recipes::RecipeRegistry;
+use crate::crafting::styles::*;
+use crate::health::events::LootDropped;
+use crate::inventory::{Inventory, ItemRegistry};
+use crate::player::components::Player;
+
+// How close the player has to stand to a station to use it
+const STATION_RANGE: f32 = 150.0;
+
+pub fn find_nearby_stations(
+    player_query: Query<&Transform, With<Player>>,
+    stations: Query<(&Transform, &Station)>,
+    mut nearby: ResMut<NearbyStations>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    let found: HashSet<Station> = stations
+        .iter()
+        .filter(|(transform, _)| {
+            transform
+                .translation
+                .truncate()
+                .distance(player.translation.truncate())
+                <= STATION_RANGE
+        })
+        .map(|(_, station)| *station)
+        .collect();
+    // Only touched when it changes, the recipe list rebuilds on change
+    if nearby.0 != found {
+        nearby.0 = found;
+    }
+}
+
+// Clicking a recipe takes its inputs & starts the craft, one at a time
+pub fn start_crafting(
+    mut buttons: Query<(&Interaction, &RecipeButton, &mut BackgroundColor), Changed<Interaction>>,
+    registry: Res<RecipeRegistry>,
+    nearby: Res<NearbyStations>,
+    mut crafting: ResMut<Crafting>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                if crafting.current.is_some() {
+                    continue;
+                }
+                let Some(recipe) = registry.recipes.get(button.0) else {
+                    continue;
+                };
+                if recipe
+                    .station
+                    .is_some_and(|station| !nearby.0.contains(&station))
+                {
+                    continue;
+                }
+                let Ok(mut inventory) = player_query.get_single_mut() else {
+                    continue;
+                };
+                if !recipe.affordable(&inventory) {
+                    continue;
+                }
+                for input in recipe.inputs.iter() {
+                    inventory.remove(&input.item, input.count);
+                }
+                crafting.current = Some(ActiveCraft {
+                    recipe: recipe.id.clone(),
+                    elapsed: 0.0,
+                    inputs: recipe.inputs.clone(),
+                });
+            }
+            Interaction::Hovered => *background = RECIPE_HOVER_COLOR.into(),
+            Interaction::None => *background = RECIPE_COLOR.into(),
+        }
+    }
+}
+
+// Finished crafts go into the inventory, anything that doesn't fit is dropped at the player's feet
+// A craft whose recipe was removed gives back its inputs the same way
+pub fn progress_crafting(
+    time: Res<Time>,
+    registry: Res<RecipeRegistry>,
+    items: Res<ItemRegistry>,
+    mut crafting: ResMut<Crafting>,
+    mut player_query: Query<(&mut Inventory, &Transform), With<Player>>,
+    mut dropped: EventWriter<LootDropped>,
+) {
+    let Some(active) = crafting.current.as_mut() else {
+        return;
+    };
+    // A craft restored from the save waits for recipes.json to load
+    if registry.recipes.is_empty() {
+        return;
+    }
+    let stacks = match registry.get(&active.recipe) {
+        Some(recipe) => {
+            active.elapsed += time.delta_seconds();
+            if active.elapsed < recipe.time {
+                return;
+            }
+            &recipe.outputs
+        }
+        None => &active.inputs,
+    };
+    let Ok((mut inventory, transform)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let mut overflow = Vec::new();
+    for stack in stacks.iter() {
+        let left = inventory.add(&items, &stack.item, stack.count);
+        if left > 0 {
+            overflow.push((stack.item.clone(), left));
+        }
+    }
+    if !overflow.is_empty() {
+        dropped.send(LootDropped {
+            position: transform.translation.truncate(),
+            items: overflow,
+        });
+    }
+    crafting.current = None;
+}
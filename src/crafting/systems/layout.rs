@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+
+use crate::crafting::components::*;
+use crate::crafting::recipes::RecipeRegistry;
+use crate::crafting::styles::*;
+use crate::inventory::{Inventory, ItemRegistry};
+use crate::player::components::Player;
+
+pub fn toggle_crafting(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    current: Res<State<CraftingScreen>>,
+    mut next: ResMut<NextState<CraftingScreen>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        next.set(match current.get() {
+            CraftingScreen::Closed => CraftingScreen::Open,
+            CraftingScreen::Open => CraftingScreen::Closed,
+        });
+    }
+}
+
+// Leaving the game always closes the crafting panel
+pub fn force_close(mut next: ResMut<NextState<CraftingScreen>>) {
+    next.set(CraftingScreen::Closed);
+}
+
+pub fn open_crafting(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(FONT);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(16.0),
+                    top: Val::Px(16.0),
+                    width: Val::Px(PANEL_WIDTH),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                z_index: ZIndex::Global(5),
+                ..Default::default()
+            },
+            CraftingPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Crafting",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: TEXT_SIZE * 1.25,
+                    color: Color::WHITE,
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font,
+                        font_size: TEXT_SIZE,
+                        color: Color::srgb(1.0, 0.85, 0.4),
+                    },
+                ),
+                CraftingProgress,
+            ));
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                RecipeList,
+            ));
+        });
+}
+
+pub fn close_crafting(mut commands: Commands, query: Query<Entity, With<CraftingPanel>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Lists every recipe the player can use here, greyed out when items are missing
+pub fn list_recipes(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<RecipeRegistry>,
+    items: Res<ItemRegistry>,
+    nearby: Res<NearbyStations>,
+    player_query: Query<Ref<Inventory>, With<Player>>,
+    list_query: Query<(Entity, Ref<RecipeList>)>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    let Ok((list, list_marker)) = list_query.get_single() else {
+        return;
+    };
+    if !inventory.is_changed() && !nearby.is_changed() && !list_marker.is_added() {
+        return;
+    }
+
+    let font = asset_server.load(FONT);
+    commands.entity(list).despawn_descendants();
+    commands.entity(list).with_children(|parent| {
+        for (index, recipe) in registry.recipes.iter().enumerate() {
+            if recipe
+                .station
+                .is_some_and(|station| !nearby.0.contains(&station))
+            {
+                continue;
+            }
+            let Some(output) = recipe.outputs.first() else {
+                continue;
+            };
+            let Some(item) = items.get(&output.item) else {
+                continue;
+            };
+            let color = if recipe.affordable(&inventory) {
+                Color::WHITE
+            } else {
+                MISSING_COLOR
+            };
+            let inputs: Vec<String> = recipe
+                .inputs
+                .iter()
+                .map(|input| {
+                    let name = items
+                        .get(&input.item)
+                        .map(|item| item.name.as_str())
+                        .unwrap_or(&input.item);
+                    format!("{} {}", input.count, name)
+                })
+                .collect();
+            let label = if output.count > 1 {
+                format!("{} x{}\n{}", item.name, output.count, inputs.join(", "))
+            } else {
+                format!("{}\n{}", item.name, inputs.join(", "))
+            };
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(8.0),
+                            padding: UiRect::all(Val::Px(4.0)),
+                            ..Default::default()
+                        },
+                        background_color: RECIPE_COLOR.into(),
+                        ..Default::default()
+                    },
+                    RecipeButton(index),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(ImageBundle {
+                        style: Style {
+                            width: Val::Px(ICON_SIZE),
+                            height: Val::Px(ICON_SIZE),
+                            ..Default::default()
+                        },
+                        image: UiImage::new(asset_server.load(item.icon.clone())),
+                        ..Default::default()
+                    });
+                    parent.spawn(TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: TEXT_SIZE,
+                            color,
+                        },
+                    ));
+                });
+        }
+    });
+}
+
+pub fn update_progress(
+    crafting: Res<Crafting>,
+    registry: Res<RecipeRegistry>,
+    items: Res<ItemRegistry>,
+    mut text_query: Query<&mut Text, With<CraftingProgress>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let progress = crafting
+        .current
+        .as_ref()
+        .and_then(|active| {
+            let recipe = registry.get(&active.recipe)?;
+            let name = recipe
+                .outputs
+                .first()
+                .and_then(|output| items.get(&output.item))
+                .map(|item| item.name.as_str())
+                .unwrap_or(&recipe.id);
+            let percent = (active.elapsed / recipe.time.max(f32::EPSILON) * 100.0).min(100.0);
+            Some(format!("Making {} {:.0}%", name, percent))
+        })
+        .unwrap_or_default();
+    if text.sections[0].value != progress {
+        text.sections[0].value = progress;
+    }
+}
@@ -0,0 +1,3 @@
+pub mod crafting;
+pub mod layout;
+pub mod saving;
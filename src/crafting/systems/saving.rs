@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+use crate::crafting::components::{ActiveCraft, Crafting};
+use crate::saves::SaveData;
+
+pub fn load_crafting(save: Res<SaveData>, mut crafting: ResMut<Crafting>) {
+    crafting.current = save.get::<Option<ActiveCraft>>("crafting").flatten();
+}
+
+pub fn save_crafting(crafting: Res<Crafting>, mut save: ResMut<SaveData>) {
+    save.set("crafting", &crafting.current);
+}
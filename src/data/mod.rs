@@ -0,0 +1,144 @@
+// === Data files ===
+// JSON under assets/ is read through the AssetServer like every other asset,
+// so it is picked up again when the file changes
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::system::{StaticSystemParam, SystemParam, SystemParamItem};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::path::Path;
+
+// Loads any asset that can be deserialized straight from a JSON file
+pub struct JsonLoader<T> {
+    extensions: &'static [&'static str],
+    asset: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonLoader<T> {
+    // Loads through a typed handle pick this loader by its asset type,
+    // folders are loaded by extension so they need one of their own
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            asset: PhantomData,
+        }
+    }
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for JsonLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
+
+// A resource built from a data file, or from each file in a data folder
+pub trait DataRegistry: Resource + FromWorld {
+    type Data: Asset + DeserializeOwned;
+    // Anything else the rebuild needs
+    type Param: SystemParam + 'static;
+
+    // Called whenever one of the files loads or changes, `name` is its file name up to the first dot
+    fn reload(&mut self, name: &str, data: &Self::Data, param: &mut SystemParamItem<Self::Param>);
+}
+
+// Keeps the file or folder behind R loaded
+#[derive(Resource)]
+pub struct DataFile<R> {
+    // Relative to assets
+    pub path: &'static str,
+    pub handle: UntypedHandle,
+    registry: PhantomData<fn() -> R>,
+}
+
+impl<R: DataRegistry> DataFile<R> {
+    // Paths without an extension are loaded as a folder
+    pub fn load(asset_server: &AssetServer, path: &'static str) -> Self {
+        let handle = if Path::new(path).extension().is_some() {
+            asset_server.load::<R::Data>(path).untyped()
+        } else {
+            asset_server.load_folder(path).untyped()
+        };
+        Self {
+            path,
+            handle,
+            registry: PhantomData,
+        }
+    }
+}
+
+pub trait DataFileApp {
+    // Registers R & keeps it up to date with the JSON at `path`
+    fn add_data_file<R: DataRegistry>(
+        &mut self,
+        path: &'static str,
+        extensions: &'static [&'static str],
+    ) -> &mut Self;
+}
+
+impl DataFileApp for App {
+    fn add_data_file<R: DataRegistry>(
+        &mut self,
+        path: &'static str,
+        extensions: &'static [&'static str],
+    ) -> &mut Self {
+        self.init_asset::<R::Data>();
+        self.register_asset_loader(JsonLoader::<R::Data>::new(extensions));
+        self.init_resource::<R>();
+        self.add_systems(
+            PreStartup,
+            move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                commands.insert_resource(DataFile::<R>::load(&asset_server, path));
+            },
+        );
+        self.add_systems(PreUpdate, reload_data::<R>)
+    }
+}
+
+// Rebuilds R from every file of its data that loaded or changed since the last frame
+pub fn reload_data<R: DataRegistry>(
+    mut events: EventReader<AssetEvent<R::Data>>,
+    file: Res<DataFile<R>>,
+    asset_server: Res<AssetServer>,
+    data: Res<Assets<R::Data>>,
+    mut registry: ResMut<R>,
+    mut param: StaticSystemParam<R::Param>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let (Some(loaded), Some(path)) = (data.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+        if !path.path().starts_with(file.path) {
+            continue;
+        }
+        let Some(name) = path
+            .path()
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.split('.').next())
+        else {
+            continue;
+        };
+        registry.reload(name, loaded, &mut param);
+    }
+}
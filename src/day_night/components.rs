@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DayPhase {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct WorldClock {
+    // 0.0 is midnight, 0.5 is midday
+    pub time_of_day: f32,
+    // Days passed since the world was created
+    pub day: u32,
+    // Real seconds in one in-game day
+    pub day_length: f32,
+}
+
+impl WorldClock {
+    pub fn new(day_length: f32) -> Self {
+        Self {
+            // Start in the morning
+            time_of_day: 0.3,
+            day: 0,
+            day_length,
+        }
+    }
+
+    pub fn advance(&mut self, seconds: f32) {
+        if self.day_length <= 0.0 {
+            return;
+        }
+        self.time_of_day += seconds / self.day_length;
+        while self.time_of_day >= 1.0 {
+            self.time_of_day -= 1.0;
+            self.day += 1;
+        }
+    }
+
+    pub fn phase(&self) -> DayPhase {
+        match self.time_of_day {
+            t if t < 0.2 => DayPhase::Night,
+            t if t < 0.3 => DayPhase::Dawn,
+            t if t < 0.7 => DayPhase::Day,
+            t if t < 0.85 => DayPhase::Dusk,
+            _ => DayPhase::Night,
+        }
+    }
+
+    // Seconds the world has been running, for timing things that outlast a session
+    pub fn seconds(&self) -> u64 {
+        (self.total_days() * self.day_length) as u64
+    }
+
+    // Total in-game days passed, including the fraction of today
+    pub fn total_days(&self) -> f32 {
+        self.day as f32 + self.time_of_day
+    }
+}
+
+// Full screen overlay that tints the world, it sits behind the rest of the UI
+#[derive(Component)]
+pub struct AmbientTint;
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::saves::SaveSet;
+use crate::states::AppState::*;
+
+pub use components::{DayPhase, WorldClock};
+pub use systems::{is_dawn, is_day, is_dusk, is_night};
+
+// Drives the WorldClock & the ambient tint over the world
+// Other systems can use the is_night/is_day/... run conditions
+pub struct DayNightPlugin {
+    // Real seconds in one in-game day
+    pub day_length: f32,
+}
+
+impl Default for DayNightPlugin {
+    fn default() -> Self {
+        Self { day_length: 600.0 }
+    }
+}
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WorldClock::new(self.day_length));
+        app.add_systems(Startup, systems::load_clock);
+        app.add_systems(OnEnter(InGame), systems::spawn_tint);
+        app.add_systems(OnExit(InGame), systems::despawn_tint);
+        app.add_systems(
+            Update,
+            (systems::advance_clock, systems::update_tint)
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(Last, systems::save_clock.in_set(SaveSet::Collect));
+    }
+}
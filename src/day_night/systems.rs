@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::day_night::components::*;
+use crate::saves::SaveData;
+
+// Tint at each point of the day, the colour between two points is blended
+const TINT_KEYS: [(f32, [f32; 4]); 7] = [
+    (0.0, [0.02, 0.03, 0.15, 0.55]),
+    (0.2, [0.02, 0.03, 0.15, 0.45]),
+    (0.25, [0.9, 0.5, 0.2, 0.2]),
+    (0.35, [1.0, 1.0, 1.0, 0.0]),
+    (0.7, [1.0, 1.0, 1.0, 0.0]),
+    (0.78, [0.85, 0.3, 0.15, 0.25]),
+    (0.9, [0.02, 0.03, 0.15, 0.55]),
+];
+
+pub fn ambient_tint(time_of_day: f32) -> Color {
+    let t = time_of_day.rem_euclid(1.0);
+    let mut previous = TINT_KEYS[TINT_KEYS.len() - 1];
+    previous.0 -= 1.0;
+    let mut next = TINT_KEYS[0];
+    for key in TINT_KEYS
+        .iter()
+        .chain(std::iter::once(&(1.0, TINT_KEYS[0].1)))
+    {
+        if key.0 > t {
+            next = *key;
+            break;
+        }
+        previous = *key;
+    }
+
+    let span = next.0 - previous.0;
+    let blend = if span > 0.0 {
+        (t - previous.0) / span
+    } else {
+        0.0
+    };
+    let mut color = [0.0; 4];
+    for (i, channel) in color.iter_mut().enumerate() {
+        *channel = previous.1[i] + (next.1[i] - previous.1[i]) * blend;
+    }
+    Color::srgba(color[0], color[1], color[2], color[3])
+}
+
+// === Run conditions ===
+
+pub fn is_night(clock: Res<WorldClock>) -> bool {
+    clock.phase() == DayPhase::Night
+}
+
+pub fn is_day(clock: Res<WorldClock>) -> bool {
+    clock.phase() == DayPhase::Day
+}
+
+pub fn is_dawn(clock: Res<WorldClock>) -> bool {
+    clock.phase() == DayPhase::Dawn
+}
+
+pub fn is_dusk(clock: Res<WorldClock>) -> bool {
+    clock.phase() == DayPhase::Dusk
+}
+
+// === Systems ===
+
+// Time<Virtual> stops while the game is paused, so the clock does too
+pub fn advance_clock(mut clock: ResMut<WorldClock>, time: Res<Time>) {
+    clock.advance(time.delta_seconds());
+}
+
+// The configured day length wins over the saved one
+pub fn load_clock(mut clock: ResMut<WorldClock>, save: Res<SaveData>) {
+    if let Some(saved) = save.get::<WorldClock>("clock") {
+        clock.time_of_day = saved.time_of_day.rem_euclid(1.0);
+        clock.day = saved.day;
+    }
+}
+
+pub fn save_clock(clock: Res<WorldClock>, mut save: ResMut<SaveData>) {
+    save.set("clock", &*clock);
+}
+
+pub fn spawn_tint(mut commands: Commands, clock: Res<WorldClock>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+            background_color: ambient_tint(clock.time_of_day).into(),
+            z_index: ZIndex::Global(-10),
+            ..Default::default()
+        },
+        AmbientTint,
+    ));
+}
+
+pub fn despawn_tint(mut commands: Commands, query: Query<Entity, With<AmbientTint>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn update_tint(
+    clock: Res<WorldClock>,
+    mut query: Query<&mut BackgroundColor, With<AmbientTint>>,
+) {
+    let tint = ambient_tint(clock.time_of_day);
+    for mut background in query.iter_mut() {
+        if background.0 != tint {
+            background.0 = tint;
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+// How deep each layer's band is, y sorted sprites stay within their band
+pub const LAYER_DEPTH: f32 = 1.0;
+// Vertical distance in world units that maps onto a whole band
+const Y_SORT_RANGE: f32 = 100_000.0;
+
+// Draw order of the world, back to front
+// The camera sits at z 10, so everything has to stay below that
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    // Terrain tiles
+    Ground,
+    // Flat things lying on the ground, shadows, dropped items
+    Decoration,
+    // The player, enemies & anything else standing upright
+    Actors,
+    // Projectiles & weather, drawn over the actors
+    Overhead,
+    // Markers & previews in world space, above everything
+    UI,
+}
+
+impl Layer {
+    // Bottom of the layer's band
+    pub const fn z(self) -> f32 {
+        match self {
+            Layer::Ground => -3.0,
+            Layer::Decoration => -2.0,
+            Layer::Actors => 0.0,
+            Layer::Overhead => 3.0,
+            Layer::UI => 5.0,
+        }
+    }
+
+    // Where in the band something with its feet at `foot_y` goes, lower on screen is drawn in front
+    pub fn sorted_z(self, foot_y: f32) -> f32 {
+        let depth = (0.5 - foot_y / Y_SORT_RANGE).clamp(0.0, 0.999);
+        self.z() + depth * LAYER_DEPTH
+    }
+}
+
+// Sorts an upright sprite against the others in its layer by where its feet are
+#[derive(Component, Debug, Clone, Copy)]
+pub struct YSort {
+    pub layer: Layer,
+    // World units from the sprite's centre down to its feet
+    pub foot: f32,
+}
+
+impl YSort {
+    pub fn new(layer: Layer, foot: f32) -> Self {
+        Self { layer, foot }
+    }
+}
@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+pub mod components;
+pub mod systems;
+
+pub use components::{Layer, YSort};
+
+// Keeps world sprites drawn in the right order
+// Everything picks its z from a Layer, upright sprites also get a YSort
+pub struct DepthPlugin;
+
+impl Plugin for DepthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            systems::y_sort.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
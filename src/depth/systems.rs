@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+use crate::depth::components::*;
+
+pub fn y_sort(mut query: Query<(&mut Transform, &YSort), Changed<Transform>>) {
+    for (mut transform, sort) in query.iter_mut() {
+        let z = sort.layer.sorted_z(transform.translation.y - sort.foot);
+        // Only write when it moves, so the change doesn't retrigger every frame
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}
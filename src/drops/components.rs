@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::ItemStack;
+
+// An item lying in the world
+#[derive(Component, Debug)]
+pub struct ItemDrop {
+    pub stack: ItemStack,
+    // Seconds since it was dropped, it despawns once this reaches DROP_LIFETIME
+    pub age: f32,
+    // Seconds before the player can pick it up, so thrown items don't come straight back
+    pub pickup_delay: f32,
+}
+
+// A drop saved into its chunk while the chunk is unloaded
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct StoredDrop {
+    pub stack: ItemStack,
+    // World units from the bottom left corner of the chunk
+    pub x: i32,
+    pub y: i32,
+    // Whole seconds since it was dropped
+    pub age: u32,
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
+pub mod components;
+pub mod systems;
+
+use crate::dungeon::systems::return_to_overworld;
+use crate::dungeon::Location;
+use crate::health::HealthSet;
+use crate::inventory::InventoryScreen;
+use crate::saves::SaveSet;
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+pub use components::StoredDrop;
+
+// Items lying in the world, from loot, harvesting & the player throwing them out (Q)
+// Drops in a chunk are stored in the Chunk while it is unloaded
+pub struct DropsPlugin;
+
+impl Plugin for DropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                systems::spawn_loot.after(HealthSet),
+                systems::restore_drops,
+                systems::stash_unloaded_drops,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                systems::throw_item
+                    .run_if(in_state(MapScreen::Closed))
+                    .run_if(in_state(InventoryScreen::Closed)),
+                systems::age_drops,
+                systems::attract_drops,
+                systems::pick_up_drops,
+                systems::merge_drops.run_if(on_timer(Duration::from_millis(500))),
+            )
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(
+            OnExit(Location::Dungeon),
+            systems::surface_drops.before(return_to_overworld),
+        );
+        app.add_systems(Last, systems::stash_all_drops.in_set(SaveSet::Prepare));
+    }
+}
@@ -0,0 +1,386 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::{thread_rng, Rng};
+
+use crate::camera::{cursor_world_position, Camera};
+use crate::depth::Layer;
+use crate::drops::components::*;
+use crate::dungeon::components::CurrentDungeon;
+use crate::health::events::LootDropped;
+use crate::inventory::{Inventory, ItemRegistry, ItemStack};
+use crate::player::components::Player;
+use crate::procedural_generation::map::*;
+
+// Seconds a drop lies around before it despawns
+const DROP_LIFETIME: f32 = 300.0;
+// Drops inside this radius fly to the player
+const MAGNET_RADIUS: f32 = 120.0;
+const MAGNET_SPEED: f32 = 400.0;
+const PICKUP_RADIUS: f32 = 24.0;
+// Drops of the same item closer than this become one
+const MERGE_RADIUS: f32 = 40.0;
+// How far loot scatters from where it dropped
+const SCATTER: f32 = 24.0;
+const THROW_DISTANCE: f32 = 70.0;
+const THROW_DELAY: f32 = 1.5;
+// Item icons are 16px
+const DROP_SCALE: f32 = 2.5;
+
+// Not a system
+fn spawn_drop(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    items: &ItemRegistry,
+    drop: ItemDrop,
+    position: Vec2,
+) {
+    let Some(item) = items.get(&drop.stack.item) else {
+        warn!("Dropping unknown item {}", drop.stack.item);
+        return;
+    };
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load(item.icon.clone()),
+            transform: Transform::from_translation(position.extend(Layer::Decoration.z() + 0.5))
+                .with_scale(Vec3::splat(DROP_SCALE)),
+            ..Default::default()
+        },
+        drop,
+    ));
+}
+
+// Loot from enemies, harvesting & anything that didn't fit in the inventory
+pub fn spawn_loot(
+    mut commands: Commands,
+    mut dropped: EventReader<LootDropped>,
+    asset_server: Res<AssetServer>,
+    items: Res<ItemRegistry>,
+) {
+    let mut rng = thread_rng();
+    for event in dropped.read() {
+        for (item, count) in event.items.iter() {
+            let offset = Vec2::new(
+                rng.gen_range(-SCATTER..=SCATTER),
+                rng.gen_range(-SCATTER..=SCATTER),
+            );
+            spawn_drop(
+                &mut commands,
+                &asset_server,
+                &items,
+                ItemDrop {
+                    stack: ItemStack::new(item, *count),
+                    age: 0.0,
+                    pickup_delay: 0.0,
+                },
+                event.position + offset,
+            );
+        }
+    }
+}
+
+// Drops stored in a chunk come back when it loads
+// Chunks loaded before items.json wait, otherwise their drops would be thrown away as unknown
+pub fn restore_drops(
+    mut commands: Commands,
+    mut loaded: EventReader<ChunkLoaded>,
+    mut rendered: ResMut<RenderedChunks>,
+    asset_server: Res<AssetServer>,
+    items: Res<ItemRegistry>,
+    mut waiting: Local<Vec<Coord>>,
+) {
+    waiting.extend(loaded.read().map(|ChunkLoaded(coord)| coord.clone()));
+    if items.0.is_empty() {
+        return;
+    }
+    for coord in waiting.drain(..) {
+        let Some(chunk) = rendered.chunks.get_mut(&coord) else {
+            continue;
+        };
+        let origin = chunk_origin(&coord);
+        for stored in std::mem::take(&mut chunk.drops) {
+            spawn_drop(
+                &mut commands,
+                &asset_server,
+                &items,
+                ItemDrop {
+                    stack: stored.stack,
+                    age: stored.age as f32,
+                    pickup_delay: 0.0,
+                },
+                origin + Vec2::new(stored.x as f32, stored.y as f32),
+            );
+        }
+    }
+}
+
+// Not a system
+fn store_drop(coord: &Coord, drop: &ItemDrop, position: Vec2) -> StoredDrop {
+    let offset = position - chunk_origin(coord);
+    StoredDrop {
+        stack: drop.stack.clone(),
+        x: offset.x as i32,
+        y: offset.y as i32,
+        age: drop.age as u32,
+    }
+}
+
+// Drops go into their chunk when it unloads
+pub fn stash_unloaded_drops(
+    mut commands: Commands,
+    mut unloaded: EventReader<ChunkUnloaded>,
+    mut derendered: ResMut<DerenderedChunks>,
+    drops: Query<(Entity, &ItemDrop, &Transform)>,
+) {
+    for ChunkUnloaded(coord) in unloaded.read() {
+        let mut stored = Vec::new();
+        for (entity, drop, transform) in drops.iter() {
+            let position = transform.translation.truncate();
+            if chunk_at(position) != *coord {
+                continue;
+            }
+            stored.push(store_drop(coord, drop, position));
+            commands.entity(entity).despawn();
+        }
+        if let Some(chunk) = derendered.chunks.get_mut(coord) {
+            chunk.drops = stored;
+        }
+    }
+}
+
+// Not a system
+// Where a drop outside the world is kept, dungeon loot is taken up to the entrance
+// & anything that went over the edge is pulled back onto the nearest tile
+fn in_world(position: Vec2, dungeon: Option<&CurrentDungeon>) -> Vec2 {
+    let coord = chunk_at(position);
+    if (0..CHUNKS).contains(&coord.x) && (0..CHUNKS).contains(&coord.y) {
+        return position;
+    }
+    if let Some(dungeon) = dungeon {
+        return dungeon.return_to;
+    }
+    let tile = tile_at(position).clamp(IVec2::ZERO, IVec2::splat(WORLD_TILES as i32 - 1));
+    let (coord, column, row) = tile_in_chunk(tile);
+    tile_position(&coord, column, row)
+}
+
+// Leaving a dungeon brings whatever was left lying in it up to the entrance
+pub fn surface_drops(
+    dungeon: Option<Res<CurrentDungeon>>,
+    mut drops: Query<&mut Transform, With<ItemDrop>>,
+) {
+    for mut transform in drops.iter_mut() {
+        let position = in_world(transform.translation.truncate(), dungeon.as_deref());
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+// Before saving, loaded chunks get a copy of the drops lying in them
+// The drops stay where they are, the copy is replaced when the chunk next unloads
+// Drops outside the loaded chunks are moved into their unloaded chunk, so none are lost
+pub fn stash_all_drops(
+    mut commands: Commands,
+    mut rendered: ResMut<RenderedChunks>,
+    mut derendered: ResMut<DerenderedChunks>,
+    dungeon: Option<Res<CurrentDungeon>>,
+    drops: Query<(Entity, &ItemDrop, &Transform)>,
+    items: Res<ItemRegistry>,
+) {
+    // Nothing has been restored yet, the chunks still hold their drops
+    if items.0.is_empty() {
+        return;
+    }
+    for chunk in rendered.chunks.values_mut() {
+        chunk.drops.clear();
+    }
+    for (entity, drop, transform) in drops.iter() {
+        let position = in_world(transform.translation.truncate(), dungeon.as_deref());
+        let coord = chunk_at(position);
+        if let Some(chunk) = rendered.chunks.get_mut(&coord) {
+            chunk.drops.push(store_drop(&coord, drop, position));
+        } else if let Some(chunk) = derendered.chunks.get_mut(&coord) {
+            chunk.drops.push(store_drop(&coord, drop, position));
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Q throws one of the item in hand towards the cursor, ctrl + Q throws the whole stack
+pub fn throw_item(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&bevy::render::camera::Camera, &GlobalTransform), With<Camera>>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    asset_server: Res<AssetServer>,
+    items: Res<ItemRegistry>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyQ) {
+        return;
+    }
+    let Ok((transform, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let stack = if keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        let selected = inventory.selected;
+        inventory.take(selected)
+    } else {
+        inventory
+            .consume_selected()
+            .map(|item| ItemStack::new(&item, 1))
+    };
+    let Some(stack) = stack else {
+        return;
+    };
+
+    let origin = transform.translation.truncate();
+    let cursor = window_query.get_single().ok().and_then(|window| {
+        let (camera, camera_transform) = camera_query.get_single().ok()?;
+        cursor_world_position(window, camera, camera_transform)
+    });
+    let direction = cursor
+        .map(|cursor| (cursor - origin).normalize_or_zero())
+        .filter(|direction| *direction != Vec2::ZERO)
+        .unwrap_or(Vec2::NEG_Y);
+
+    spawn_drop(
+        &mut commands,
+        &asset_server,
+        &items,
+        ItemDrop {
+            stack,
+            age: 0.0,
+            pickup_delay: THROW_DELAY,
+        },
+        origin + direction * THROW_DISTANCE,
+    );
+}
+
+pub fn age_drops(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut drops: Query<(Entity, &mut ItemDrop)>,
+) {
+    for (entity, mut drop) in drops.iter_mut() {
+        drop.age += time.delta_seconds();
+        drop.pickup_delay = (drop.pickup_delay - time.delta_seconds()).max(0.0);
+        if drop.age >= DROP_LIFETIME {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Drops near the player are pulled in, faster the closer they get
+pub fn attract_drops(
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    mut drops: Query<(&ItemDrop, &mut Transform), Without<Player>>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    let target = player.translation.truncate();
+    for (drop, mut transform) in drops.iter_mut() {
+        if drop.pickup_delay > 0.0 {
+            continue;
+        }
+        let offset = target - transform.translation.truncate();
+        let distance = offset.length();
+        if distance > MAGNET_RADIUS || distance <= f32::EPSILON {
+            continue;
+        }
+        let pull = 1.0 - distance / MAGNET_RADIUS;
+        let step = (MAGNET_SPEED * (0.25 + pull) * time.delta_seconds()).min(distance);
+        transform.translation += (offset / distance * step).extend(0.0);
+    }
+}
+
+// Whatever doesn't fit stays on the ground
+pub fn pick_up_drops(
+    mut commands: Commands,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut drops: Query<(Entity, &mut ItemDrop, &Transform), Without<Player>>,
+    items: Res<ItemRegistry>,
+) {
+    let Ok((player, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let target = player.translation.truncate();
+    for (entity, mut drop, transform) in drops.iter_mut() {
+        if drop.pickup_delay > 0.0
+            || transform.translation.truncate().distance(target) > PICKUP_RADIUS
+        {
+            continue;
+        }
+        let left = inventory.add(&items, &drop.stack.item, drop.stack.count);
+        if left == 0 {
+            commands.entity(entity).despawn();
+        } else if left != drop.stack.count {
+            drop.stack.count = left;
+        } else {
+            // Inventory is full, stop it sitting on the player
+            drop.pickup_delay = THROW_DELAY;
+        }
+    }
+}
+
+// Nearby drops of the same item become one stack
+pub fn merge_drops(mut commands: Commands, mut drops: Query<(Entity, &mut ItemDrop, &Transform)>) {
+    let snapshot: Vec<(Entity, String, u32, Vec2)> = drops
+        .iter()
+        .map(|(entity, drop, transform)| {
+            (
+                entity,
+                drop.stack.item.clone(),
+                drop.stack.count,
+                transform.translation.truncate(),
+            )
+        })
+        .collect();
+    let mut merged = vec![false; snapshot.len()];
+
+    for i in 0..snapshot.len() {
+        if merged[i] {
+            continue;
+        }
+        let (keep, item, mut count, position) = snapshot[i].clone();
+        for j in i + 1..snapshot.len() {
+            let (other, other_item, other_count, other_position) = &snapshot[j];
+            if merged[j] || *other_item != item || position.distance(*other_position) > MERGE_RADIUS
+            {
+                continue;
+            }
+            count += other_count;
+            merged[j] = true;
+            commands.entity(*other).despawn();
+        }
+        if count != snapshot[i].2 {
+            if let Ok((_, mut drop, _)) = drops.get_mut(keep) {
+                drop.stack.count = count;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_past_the_edge_are_kept_in_the_world() {
+        let inside = tile_position(&Coord { x: 3, y: 4 }, 2, 5);
+        assert_eq!(in_world(inside, None), inside);
+
+        let last = WORLD_TILES as i32 - 1;
+        for (position, tile) in [
+            (Vec2::new(-500.0, -500.0), IVec2::ZERO),
+            (Vec2::new(1e6, 1e6), IVec2::splat(last)),
+            (
+                Vec2::new(inside.x, -40_000.0),
+                IVec2::new(tile_at(inside).x, 0),
+            ),
+        ] {
+            let kept = in_world(position, None);
+            assert_eq!(tile_at(kept), tile);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::procedural_generation::map::{tile_at, Coord, TILE_HEIGHT, TILE_WIDTH};
+
+// Dungeons are laid out far below the overworld, so the chunk loader unloads the world around the entrance
+// while the player is down there & loads it back untouched when they come up
+pub const DUNGEON_ORIGIN: Vec2 = Vec2::new(0.0, -40_000.0);
+// Width & height of a dungeon in tiles
+pub const DUNGEON_SIZE: i32 = 48;
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    #[default]
+    Overworld,
+    Dungeon,
+}
+
+// A way down into a dungeon, placed in a chunk by the entrances pass
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Entrance {
+    pub column: usize,
+    pub row: usize,
+    // The dungeon's chest only gives its loot once
+    #[serde(default)]
+    pub looted: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DungeonTile {
+    // Solid rock nobody ever sees, left undrawn
+    Rock,
+    Floor,
+    // Rock bordering a room or corridor
+    Wall,
+    // Stairs back up to the overworld
+    Exit,
+}
+
+impl DungeonTile {
+    pub fn is_walkable(&self) -> bool {
+        matches!(self, DungeonTile::Floor | DungeonTile::Exit)
+    }
+}
+
+// Rooms cover min up to but not including max
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Room {
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+impl Room {
+    pub fn size(&self) -> IVec2 {
+        self.max - self.min
+    }
+
+    pub fn centre(&self) -> IVec2 {
+        (self.min + self.max) / 2
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Dungeon {
+    // tiles[y][x], y 0 at the bottom like chunks
+    pub tiles: Vec<Vec<DungeonTile>>,
+    pub rooms: Vec<Room>,
+    // Where the player arrives & leaves from
+    pub exit: IVec2,
+    // In the room furthest from the exit
+    pub chest: IVec2,
+}
+
+impl Dungeon {
+    // Anything outside the dungeon is rock
+    pub fn tile(&self, tile: IVec2) -> DungeonTile {
+        self.tiles
+            .get(tile.y as usize)
+            .and_then(|row| row.get(tile.x as usize))
+            .copied()
+            .unwrap_or(DungeonTile::Rock)
+    }
+}
+
+// World position of a dungeon tile
+pub fn dungeon_position(tile: IVec2) -> Vec2 {
+    DUNGEON_ORIGIN + Vec2::new(tile.x as f32 * TILE_WIDTH, tile.y as f32 * TILE_HEIGHT)
+}
+
+// The dungeon tile covering a world position
+pub fn dungeon_tile_at(position: Vec2) -> IVec2 {
+    tile_at(position - DUNGEON_ORIGIN)
+}
+
+// The dungeon the player is in, only present while Location::Dungeon
+#[derive(Resource, Debug)]
+pub struct CurrentDungeon {
+    pub layout: Dungeon,
+    // Chunk & tile of the entrance that was taken
+    pub chunk: Coord,
+    pub column: usize,
+    pub row: usize,
+    pub looted: bool,
+    // Where the player stood before going down
+    pub return_to: Vec2,
+    // Last spot the player stood that wasn't inside a wall
+    pub last_position: Vec2,
+}
+
+// Sent when a chunk's entrances change, so its sprites are rebuilt
+#[derive(Event, Debug, Clone)]
+pub struct EntrancesChanged(pub Coord);
+
+#[derive(Component)]
+pub struct DungeonSprite;
+
+#[derive(Component)]
+pub struct ChestSprite;
@@ -0,0 +1,257 @@
+// === Dungeon generation ===
+// Rooms & corridors carved out of solid rock by splitting the dungeon in two over & over (BSP)
+// A dungeon only depends on its seed, so it's rebuilt every time it's entered instead of being saved
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::dungeon::components::*;
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::map::Coord;
+use crate::procedural_generation::props::prop_on_tile;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+
+// Smallest area a split can leave on either side
+const MIN_AREA: i32 = 10;
+const MIN_ROOM: i32 = 4;
+// Tiles tried before a chunk gives up on its entrance
+const ENTRANCE_TRIES: usize = 8;
+
+fn entrance_chance(biome: BiomeType) -> f64 {
+    match biome {
+        BiomeType::Mountain => 0.35,
+        BiomeType::Desert | BiomeType::Tundra => 0.15,
+        BiomeType::Forest => 0.1,
+        BiomeType::Plains => 0.05,
+        _ => 0.0,
+    }
+}
+
+// Rocky biomes are the likeliest to have a way down, never in water, under a prop or on ore
+pub fn scatter_entrances(chunk: &mut Chunk, coord: &Coord, seed: &WorldSeed) {
+    chunk.entrances.clear();
+    let mut rng = seed.chunk_rng(coord, Pass::Entrances);
+    if !rng.gen_bool(entrance_chance(chunk.biome)) {
+        return;
+    }
+    for _ in 0..ENTRANCE_TRIES {
+        let column = rng.gen_range(0..CHUNK_SIZE);
+        let row = rng.gen_range(0..CHUNK_SIZE);
+        let water = chunk.tiles[row][column].tile_type == TileType::Water;
+        let ore = chunk
+            .ores
+            .iter()
+            .any(|ore| ore.column == column && ore.row == row);
+        if !water && !ore && !prop_on_tile(chunk, coord, column, row) {
+            chunk.entrances.push(Entrance {
+                column,
+                row,
+                looted: false,
+            });
+            return;
+        }
+    }
+}
+
+pub fn generate_dungeon(seed: u64) -> Dungeon {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = DUNGEON_SIZE as usize;
+    let mut dungeon = Dungeon {
+        tiles: vec![vec![DungeonTile::Rock; size]; size],
+        rooms: Vec::new(),
+        exit: IVec2::ZERO,
+        chest: IVec2::ZERO,
+    };
+    // A tile of rock all the way round leaves space for the outer walls
+    let area = Room {
+        min: IVec2::ONE,
+        max: IVec2::splat(DUNGEON_SIZE - 1),
+    };
+    split(&mut dungeon, &mut rng, area);
+
+    dungeon.exit = dungeon.rooms[0].centre();
+    dungeon.chest = dungeon
+        .rooms
+        .iter()
+        .map(Room::centre)
+        .max_by_key(|centre| (*centre - dungeon.exit).length_squared())
+        .unwrap_or(dungeon.exit);
+    let exit = dungeon.exit;
+    dungeon.tiles[exit.y as usize][exit.x as usize] = DungeonTile::Exit;
+    build_walls(&mut dungeon);
+    dungeon
+}
+
+// Splits an area in two & joins the halves with a corridor, an area too small to split gets a room
+// Returns a floor tile inside the area for a corridor to join up with
+fn split(dungeon: &mut Dungeon, rng: &mut StdRng, area: Room) -> IVec2 {
+    let size = area.size();
+    let across_x = match (size.x >= MIN_AREA * 2, size.y >= MIN_AREA * 2) {
+        (false, false) => return place_room(dungeon, rng, area),
+        (true, false) => true,
+        (false, true) => false,
+        // Cutting across the longer side keeps areas from getting long & thin
+        (true, true) => size.x > size.y || (size.x == size.y && rng.gen_bool(0.5)),
+    };
+    let (first, second) = if across_x {
+        let at = rng.gen_range(area.min.x + MIN_AREA..=area.max.x - MIN_AREA);
+        (
+            Room {
+                min: area.min,
+                max: IVec2::new(at, area.max.y),
+            },
+            Room {
+                min: IVec2::new(at, area.min.y),
+                max: area.max,
+            },
+        )
+    } else {
+        let at = rng.gen_range(area.min.y + MIN_AREA..=area.max.y - MIN_AREA);
+        (
+            Room {
+                min: area.min,
+                max: IVec2::new(area.max.x, at),
+            },
+            Room {
+                min: IVec2::new(area.min.x, at),
+                max: area.max,
+            },
+        )
+    };
+    let from = split(dungeon, rng, first);
+    let to = split(dungeon, rng, second);
+    carve_corridor(dungeon, rng, from, to);
+    if rng.gen_bool(0.5) {
+        from
+    } else {
+        to
+    }
+}
+
+// Not a system
+fn place_room(dungeon: &mut Dungeon, rng: &mut StdRng, area: Room) -> IVec2 {
+    // A tile of margin inside the area keeps rooms in neighbouring areas from running together
+    let size = area.size();
+    let width = rng.gen_range(MIN_ROOM..=size.x - 2);
+    let height = rng.gen_range(MIN_ROOM..=size.y - 2);
+    let min = IVec2::new(
+        rng.gen_range(area.min.x + 1..=area.max.x - 1 - width),
+        rng.gen_range(area.min.y + 1..=area.max.y - 1 - height),
+    );
+    let room = Room {
+        min,
+        max: min + IVec2::new(width, height),
+    };
+    for y in room.min.y..room.max.y {
+        for x in room.min.x..room.max.x {
+            dungeon.tiles[y as usize][x as usize] = DungeonTile::Floor;
+        }
+    }
+    dungeon.rooms.push(room);
+    room.centre()
+}
+
+// An L shaped corridor, bending one way or the other
+fn carve_corridor(dungeon: &mut Dungeon, rng: &mut StdRng, from: IVec2, to: IVec2) {
+    let corner = if rng.gen_bool(0.5) {
+        IVec2::new(to.x, from.y)
+    } else {
+        IVec2::new(from.x, to.y)
+    };
+    for (start, end) in [(from, corner), (corner, to)] {
+        let step = (end - start).signum();
+        let mut tile = start;
+        loop {
+            dungeon.tiles[tile.y as usize][tile.x as usize] = DungeonTile::Floor;
+            if tile == end {
+                break;
+            }
+            tile += step;
+        }
+    }
+}
+
+// Rock touching a floor, even diagonally, is turned into wall
+fn build_walls(dungeon: &mut Dungeon) {
+    for y in 0..DUNGEON_SIZE {
+        for x in 0..DUNGEON_SIZE {
+            let tile = IVec2::new(x, y);
+            if dungeon.tile(tile) != DungeonTile::Rock {
+                continue;
+            }
+            let open = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| IVec2::new(dx, dy)))
+                .any(|offset| dungeon.tile(tile + offset).is_walkable());
+            if open {
+                dungeon.tiles[y as usize][x as usize] = DungeonTile::Wall;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashSet, VecDeque};
+
+    // Every walkable tile that can be walked to from the exit
+    fn reachable(dungeon: &Dungeon) -> HashSet<IVec2> {
+        let mut seen = HashSet::from([dungeon.exit]);
+        let mut queue = VecDeque::from([dungeon.exit]);
+        while let Some(tile) = queue.pop_front() {
+            for step in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let next = tile + step;
+                if dungeon.tile(next).is_walkable() && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_dungeon() {
+        for seed in 0..20 {
+            let (first, second) = (generate_dungeon(seed), generate_dungeon(seed));
+            assert_eq!(first.tiles, second.tiles);
+            assert_eq!(first.rooms, second.rooms);
+            assert_eq!((first.exit, first.chest), (second.exit, second.chest));
+        }
+        assert_ne!(generate_dungeon(1).tiles, generate_dungeon(2).tiles);
+    }
+
+    #[test]
+    fn every_room_can_be_reached_from_the_exit() {
+        for seed in 0..50 {
+            let dungeon = generate_dungeon(seed);
+            let reached = reachable(&dungeon);
+            for room in dungeon.rooms.iter() {
+                assert!(reached.contains(&room.centre()), "seed {seed}: {room:?}");
+            }
+            // No floor is cut off either
+            for (y, row) in dungeon.tiles.iter().enumerate() {
+                for (x, tile) in row.iter().enumerate() {
+                    let at = IVec2::new(x as i32, y as i32);
+                    assert!(
+                        !tile.is_walkable() || reached.contains(&at),
+                        "seed {seed}: {at}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_chest_sits_on_the_floor() {
+        for seed in 0..50 {
+            let dungeon = generate_dungeon(seed);
+            assert_eq!(dungeon.tile(dungeon.exit), DungeonTile::Exit);
+            assert_eq!(
+                dungeon.tile(dungeon.chest),
+                DungeonTile::Floor,
+                "seed {seed}"
+            );
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod generation;
+pub mod systems;
+
+use crate::crafting::CraftingScreen;
+use crate::inventory::InventoryScreen;
+use crate::player::systems::movement::movement_system;
+use crate::player::systems::spawning::despawn_player;
+use crate::procedural_generation::chunk_sprites::ChunkSpritesPlugin;
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+pub use components::{Entrance, Location};
+
+// Entrances scattered through the overworld lead down into seeded dungeons of rooms & corridors
+// drawn with the tiny dungeon tiles, F goes down & F on the stairs comes back up
+pub struct DungeonPlugin;
+
+impl Plugin for DungeonPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<Location>();
+        app.add_plugins(ChunkSpritesPlugin::<components::EntrancesChanged>::default());
+        app.add_systems(
+            Update,
+            systems::enter_dungeon
+                .run_if(in_state(InGame))
+                .run_if(in_state(Location::Overworld))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+        app.add_systems(
+            Update,
+            systems::keep_out_of_walls
+                .after(movement_system)
+                .run_if(in_state(InGame))
+                .run_if(in_state(Location::Dungeon)),
+        );
+        app.add_systems(
+            Update,
+            (systems::open_chest, systems::leave_dungeon)
+                .chain()
+                .run_if(in_state(InGame))
+                .run_if(in_state(Location::Dungeon))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+        app.add_systems(OnEnter(Location::Dungeon), systems::spawn_dungeon);
+        app.add_systems(OnExit(Location::Dungeon), systems::return_to_overworld);
+        app.add_systems(
+            OnExit(InGame),
+            systems::surface_on_exit.before(despawn_player),
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use rand::thread_rng;
+
+use crate::camera::Camera;
+use crate::depth::Layer;
+use crate::dungeon::components::*;
+use crate::dungeon::generation::generate_dungeon;
+use crate::health::components::LootTable;
+use crate::health::events::LootDropped;
+use crate::health::systems::roll_loot;
+use crate::player::components::Player;
+use crate::procedural_generation::chunk::{Chunk, CHUNK_SIZE};
+use crate::procedural_generation::chunk_sprites::{ChunkSprites, SpriteSpawner};
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::props::loot;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+use crate::procedural_generation::tile_map::TileMap;
+
+// How close the player has to be to an entrance, the stairs or the chest to use them
+const INTERACT_RANGE: f32 = 80.0;
+// Tiny dungeon tiles are 16px, this makes them a tile wide
+const DUNGEON_SCALE: f32 = TILE_WIDTH / 16.0;
+
+const ENTRANCE_SPRITE: &str = "kenney_tiny-dungeon/Tiles/tile_0009.png";
+const FLOOR_SPRITES: [&str; 2] = [
+    "kenney_tiny-dungeon/Tiles/tile_0048.png",
+    "kenney_tiny-dungeon/Tiles/tile_0049.png",
+];
+const WALL_SPRITE: &str = "kenney_tiny-dungeon/Tiles/tile_0040.png";
+const EXIT_SPRITE: &str = "kenney_tiny-dungeon/Tiles/tile_0039.png";
+const CHEST_SPRITE: &str = "kenney_tiny-dungeon/Tiles/tile_0089.png";
+
+fn chest_loot() -> LootTable {
+    LootTable(vec![
+        loot("copper_ingot", 0.8, 2, 5),
+        loot("iron_ingot", 0.5, 1, 3),
+        loot("gold_ingot", 0.25, 1, 2),
+        loot("bone", 0.6, 2, 4),
+    ])
+}
+
+// Not a system
+// Moves the player & drags the camera along by the same amount, so the view doesn't jump
+pub fn teleport(player: &mut Transform, camera: Option<Mut<Transform>>, to: Vec2) {
+    let offset = to - player.translation.truncate();
+    player.translation += offset.extend(0.0);
+    if let Some(mut camera) = camera {
+        camera.translation += offset.extend(0.0);
+    }
+}
+
+// === Overworld ===
+
+impl ChunkSprites for EntrancesChanged {
+    type Param = Res<'static, AssetServer>;
+
+    fn chunk(&self) -> &Coord {
+        &self.0
+    }
+
+    fn spawn(
+        sprites: &mut SpriteSpawner,
+        coord: &Coord,
+        chunk: &Chunk,
+        asset_server: &mut Res<AssetServer>,
+    ) {
+        for entrance in chunk.entrances.iter() {
+            let position = tile_position(coord, entrance.column, entrance.row);
+            sprites.spawn(SpriteBundle {
+                texture: asset_server.load(ENTRANCE_SPRITE),
+                transform: Transform::from_translation(position.extend(Layer::Ground.z() + 0.25))
+                    .with_scale(Vec3::splat(DUNGEON_SCALE)),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+// F beside an entrance takes the player down into its dungeon
+// Each entrance's dungeon is seeded from the world seed & where the entrance is
+pub fn enter_dungeon(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Transform, With<Player>>,
+    rendered: Res<RenderedChunks>,
+    seed: Res<WorldSeed>,
+    mut location: ResMut<NextState<Location>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player = player_transform.translation.truncate();
+
+    let nearest = rendered
+        .chunks
+        .iter()
+        .flat_map(|(coord, chunk)| {
+            chunk
+                .entrances
+                .iter()
+                .map(move |entrance| (coord, entrance))
+        })
+        .map(|(coord, entrance)| {
+            let position = tile_position(coord, entrance.column, entrance.row);
+            (coord, entrance, position.distance(player))
+        })
+        .filter(|(_, _, distance)| *distance <= INTERACT_RANGE)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+    let Some((coord, entrance, _)) = nearest else {
+        return;
+    };
+
+    let tile = IVec2::new(
+        coord.x * CHUNK_SIZE as i32 + entrance.column as i32,
+        coord.y * CHUNK_SIZE as i32 + entrance.row as i32,
+    );
+    let layout = generate_dungeon(seed.hash(tile.x, tile.y, Pass::Dungeons));
+    let arrival = dungeon_position(layout.exit);
+    commands.insert_resource(CurrentDungeon {
+        layout,
+        chunk: coord.clone(),
+        column: entrance.column,
+        row: entrance.row,
+        looted: entrance.looted,
+        return_to: player,
+        last_position: arrival,
+    });
+    location.set(Location::Dungeon);
+}
+
+// === Dungeon ===
+
+pub fn spawn_dungeon(
+    mut commands: Commands,
+    dungeon: Res<CurrentDungeon>,
+    asset_server: Res<AssetServer>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    let layout = &dungeon.layout;
+    for y in 0..DUNGEON_SIZE {
+        for x in 0..DUNGEON_SIZE {
+            let tile = IVec2::new(x, y);
+            let sprite = match layout.tile(tile) {
+                DungeonTile::Rock => continue,
+                // Mostly plain floor with the odd cracked tile
+                DungeonTile::Floor => FLOOR_SPRITES[((x * 7 + y * 13) % 5 == 0) as usize],
+                DungeonTile::Wall => WALL_SPRITE,
+                DungeonTile::Exit => EXIT_SPRITE,
+            };
+            commands.spawn((
+                SpriteBundle {
+                    texture: asset_server.load(sprite),
+                    transform: Transform::from_translation(
+                        dungeon_position(tile).extend(Layer::Ground.z()),
+                    )
+                    .with_scale(Vec3::splat(DUNGEON_SCALE)),
+                    ..Default::default()
+                },
+                DungeonSprite,
+            ));
+        }
+    }
+    if !dungeon.looted {
+        commands.spawn((
+            SpriteBundle {
+                texture: asset_server.load(CHEST_SPRITE),
+                transform: Transform::from_translation(
+                    dungeon_position(layout.chest).extend(Layer::Decoration.z()),
+                )
+                .with_scale(Vec3::splat(DUNGEON_SCALE)),
+                ..Default::default()
+            },
+            DungeonSprite,
+            ChestSprite,
+        ));
+    }
+
+    if let Ok(mut player) = player_query.get_single_mut() {
+        teleport(
+            &mut player,
+            camera_query.get_single_mut().ok(),
+            dungeon_position(layout.exit),
+        );
+    }
+}
+
+// There's no collision in the overworld, but dungeon walls are solid
+// A blocked move slides along the wall when only one direction is blocked
+pub fn keep_out_of_walls(
+    mut dungeon: ResMut<CurrentDungeon>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+    let position = player.translation.truncate();
+    let previous = dungeon.last_position;
+    let walkable = |point: &Vec2| dungeon.layout.tile(dungeon_tile_at(*point)).is_walkable();
+    let allowed = [
+        position,
+        Vec2::new(position.x, previous.y),
+        Vec2::new(previous.x, position.y),
+    ]
+    .into_iter()
+    .find(walkable)
+    .unwrap_or(previous);
+
+    if allowed != position {
+        teleport(&mut player, camera_query.get_single_mut().ok(), allowed);
+    }
+    dungeon.last_position = allowed;
+}
+
+// F on the stairs heads back up
+pub fn leave_dungeon(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Transform, With<Player>>,
+    dungeon: Res<CurrentDungeon>,
+    mut location: ResMut<NextState<Location>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let exit = dungeon_position(dungeon.layout.exit);
+    if exit.distance(player_transform.translation.truncate()) <= INTERACT_RANGE {
+        location.set(Location::Overworld);
+    }
+}
+
+// F by the chest spills its loot, the entrance remembers so it can't be looted again
+pub fn open_chest(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Transform, With<Player>>,
+    chest_query: Query<Entity, With<ChestSprite>>,
+    mut dungeon: ResMut<CurrentDungeon>,
+    mut tile_map: TileMap,
+    mut dropped: EventWriter<LootDropped>,
+) {
+    if dungeon.looted || !keyboard_input.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let chest = dungeon_position(dungeon.layout.chest);
+    if chest.distance(player_transform.translation.truncate()) > INTERACT_RANGE {
+        return;
+    }
+
+    dungeon.looted = true;
+    let (column, row) = (dungeon.column, dungeon.row);
+    // The entrance's chunk is unloaded while the player is down here
+    if let Some(entrance) = tile_map.chunk_mut(&dungeon.chunk).and_then(|chunk| {
+        chunk
+            .entrances
+            .iter_mut()
+            .find(|entrance| entrance.column == column && entrance.row == row)
+    }) {
+        entrance.looted = true;
+    }
+    for entity in chest_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let items = roll_loot(&chest_loot(), &mut thread_rng());
+    if !items.is_empty() {
+        dropped.send(LootDropped {
+            position: chest,
+            items,
+        });
+    }
+}
+
+// Clears the dungeon away & puts the player back where they went down
+pub fn return_to_overworld(
+    mut commands: Commands,
+    dungeon: Option<Res<CurrentDungeon>>,
+    sprites: Query<Entity, With<DungeonSprite>>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    for entity in sprites.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(dungeon) = dungeon else {
+        return;
+    };
+    if let Ok(mut player) = player_query.get_single_mut() {
+        teleport(
+            &mut player,
+            camera_query.get_single_mut().ok(),
+            dungeon.return_to,
+        );
+    }
+    commands.remove_resource::<CurrentDungeon>();
+}
+
+// Leaving the game from a dungeon brings the player up first, so they come back to the overworld
+pub fn surface_on_exit(
+    dungeon: Option<Res<CurrentDungeon>>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    mut location: ResMut<NextState<Location>>,
+) {
+    let Some(dungeon) = dungeon else {
+        return;
+    };
+    if let Ok(mut player) = player_query.get_single_mut() {
+        teleport(
+            &mut player,
+            camera_query.get_single_mut().ok(),
+            dungeon.return_to,
+        );
+    }
+    location.set(Location::Overworld);
+}
@@ -0,0 +1,22 @@
+// Components for hostile creatures
+
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Enemy {
+    pub speed: f32,
+    // Radius used for collision checks
+    pub radius: f32,
+    // Damage dealt to the player on contact
+    pub damage: f32,
+}
+
+// Time until the enemy can hurt the player again
+#[derive(Component)]
+pub struct AttackCooldown(pub Timer);
+
+impl Default for AttackCooldown {
+    fn default() -> Self {
+        Self(Timer::from_seconds(1.0, TimerMode::Once))
+    }
+}
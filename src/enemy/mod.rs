@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::dungeon::Location;
+use crate::health::HealthSet;
+use crate::states::AppState::*;
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(systems::EnemySpawnTimer::default());
+        app.add_systems(
+            Update,
+            (
+                systems::spawn_enemies.run_if(in_state(Location::Overworld)),
+                systems::chase_player,
+                systems::contact_damage,
+            )
+                .before(HealthSet)
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(OnExit(InGame), systems::despawn_enemies);
+        // Nothing follows the player down into a dungeon
+        app.add_systems(OnEnter(Location::Dungeon), systems::despawn_enemies);
+    }
+}
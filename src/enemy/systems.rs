@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::depth::{Layer, YSort};
+use crate::enemy::components::*;
+use crate::health::components::*;
+use crate::health::events::*;
+use crate::player::components::Player;
+
+pub const MAX_ENEMIES: usize = 8;
+const PLAYER_RADIUS: f32 = 30.0;
+// Half of the 16px sprite, scaled up 4x
+const ENEMY_FOOT: f32 = 32.0;
+
+#[derive(Resource)]
+pub struct EnemySpawnTimer(pub Timer);
+
+impl Default for EnemySpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(3.0, TimerMode::Repeating))
+    }
+}
+
+pub fn spawn_enemies(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut timer: ResMut<EnemySpawnTimer>,
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    if enemy_query.iter().count() >= MAX_ENEMIES {
+        return;
+    }
+
+    // Spawn just off screen in a random direction from the player
+    let mut rng = thread_rng();
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let distance = rng.gen_range(600.0..900.0);
+    let offset = Vec2::from_angle(angle) * distance;
+    let translation = player_transform.translation.truncate() + offset;
+
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("kenney_tiny-dungeon/Tiles/tile_0108.png"),
+            transform: Transform::from_translation(translation.extend(Layer::Actors.z()))
+                .with_scale(Vec3::splat(4.0)),
+            ..Default::default()
+        },
+        Enemy {
+            speed: 120.0,
+            radius: 28.0,
+            damage: 10.0,
+        },
+        AttackCooldown::default(),
+        YSort::new(Layer::Actors, ENEMY_FOOT),
+        Health::new(30.0, 0.0),
+        Resistances::default().with(DamageType::Poison, 0.5),
+        XpReward(10),
+        LootTable(vec![LootEntry {
+            item: "bone".to_string(),
+            chance: 0.5,
+            min: 1,
+            max: 2,
+        }]),
+    ));
+}
+
+pub fn chase_player(
+    mut enemy_query: Query<(&mut Transform, &Enemy), Without<Player>>,
+    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    for (mut transform, enemy) in enemy_query.iter_mut() {
+        let direction = (player_transform.translation - transform.translation)
+            .truncate()
+            .normalize_or_zero();
+        transform.translation += (direction * enemy.speed * time.delta_seconds()).extend(0.0);
+    }
+}
+
+pub fn contact_damage(
+    mut enemy_query: Query<(Entity, &Transform, &Enemy, &mut AttackCooldown), Without<Dead>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut damage: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    let Ok((player, player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    for (entity, transform, enemy, mut cooldown) in enemy_query.iter_mut() {
+        cooldown.0.tick(time.delta());
+        if !cooldown.0.finished() {
+            continue;
+        }
+        let distance = transform.translation.truncate().distance(player_position);
+        if distance < enemy.radius + PLAYER_RADIUS {
+            damage.send(DamageEvent {
+                target: player,
+                amount: enemy.damage,
+                damage_type: DamageType::Physical,
+                source: Some(entity),
+            });
+            cooldown.0.reset();
+        }
+    }
+}
+
+pub fn despawn_enemies(mut commands: Commands, query: Query<Entity, With<Enemy>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::health::components::LootTable;
+use crate::procedural_generation::chunk::BiomeType;
+use crate::procedural_generation::map::Coord;
+use crate::procedural_generation::props::loot;
+
+// Growth points a crop gains each world clock second, watered or not
+pub const WATERED_GROWTH: u64 = 4;
+pub const DRY_GROWTH: u64 = 1;
+// World clock seconds one watering lasts
+pub const WATERED_FOR: u64 = 240;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CropKind {
+    Wheat,
+    Carrot,
+}
+
+pub struct CropDefinition {
+    // Item planted to grow it
+    pub seed: &'static str,
+    // One sprite per growth stage, the last stage is ripe
+    pub stages: &'static [&'static str],
+    // World clock seconds each stage takes while watered
+    pub stage_time: u64,
+    pub yields: LootTable,
+    // Where it can be planted, nothing grows in the Tundra
+    pub biomes: &'static [BiomeType],
+}
+
+impl CropKind {
+    pub const ALL: [CropKind; 2] = [CropKind::Wheat, CropKind::Carrot];
+
+    pub fn definition(&self) -> CropDefinition {
+        match self {
+            CropKind::Wheat => CropDefinition {
+                seed: "wheat_seeds",
+                stages: &[
+                    "sprites/crops/wheat_0.png",
+                    "sprites/crops/wheat_1.png",
+                    "sprites/crops/wheat_2.png",
+                    "sprites/crops/wheat_3.png",
+                ],
+                stage_time: 90,
+                yields: LootTable(vec![
+                    loot("wheat", 1.0, 1, 3),
+                    loot("wheat_seeds", 0.8, 1, 2),
+                ]),
+                biomes: &[BiomeType::Plains, BiomeType::Forest, BiomeType::Desert],
+            },
+            CropKind::Carrot => CropDefinition {
+                seed: "carrot_seeds",
+                stages: &[
+                    "sprites/crops/carrot_0.png",
+                    "sprites/crops/carrot_1.png",
+                    "sprites/crops/carrot_2.png",
+                    "sprites/crops/carrot_3.png",
+                ],
+                stage_time: 120,
+                yields: LootTable(vec![
+                    loot("carrot", 1.0, 1, 2),
+                    loot("carrot_seeds", 0.6, 1, 2),
+                ]),
+                biomes: &[BiomeType::Plains, BiomeType::Forest],
+            },
+        }
+    }
+
+    pub fn from_seed(item: &str) -> Option<CropKind> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.definition().seed == item)
+    }
+}
+
+// A crop planted in a chunk, growth is worked out from the world clock
+// so it catches up on however long its chunk was unloaded
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Crop {
+    pub kind: CropKind,
+    // Tile within the chunk
+    pub column: usize,
+    pub row: usize,
+    pub growth: u64,
+    // World clock seconds growth was last worked out at
+    pub updated_at: u64,
+    pub watered_until: u64,
+}
+
+impl Crop {
+    pub fn new(kind: CropKind, column: usize, row: usize, now: u64) -> Self {
+        Self {
+            kind,
+            column,
+            row,
+            growth: 0,
+            updated_at: now,
+            watered_until: now,
+        }
+    }
+
+    // Adds the growth since it was last updated, the watered part of that time counts in full
+    pub fn advance(&mut self, now: u64) {
+        if now <= self.updated_at {
+            return;
+        }
+        let elapsed = now - self.updated_at;
+        let watered = self.watered_until.min(now).saturating_sub(self.updated_at);
+        self.growth += watered * WATERED_GROWTH + (elapsed - watered) * DRY_GROWTH;
+        self.updated_at = now;
+    }
+
+    pub fn water(&mut self, now: u64) {
+        // Growth up to now counts at the old rate
+        self.advance(now);
+        self.watered_until = self.watered_until.max(now + WATERED_FOR);
+    }
+
+    pub fn is_watered(&self, now: u64) -> bool {
+        self.watered_until > now
+    }
+
+    pub fn stage(&self) -> usize {
+        let definition = self.kind.definition();
+        let stage = self.growth / (definition.stage_time * WATERED_GROWTH);
+        (stage as usize).min(definition.stages.len() - 1)
+    }
+
+    pub fn is_ripe(&self) -> bool {
+        self.stage() == self.kind.definition().stages.len() - 1
+    }
+}
+
+// Sent when a chunk's crops change how they look, so its sprites are rebuilt
+#[derive(Event, Debug, Clone)]
+pub struct CropsChanged(pub Coord);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dry_crops_grow_slowly() {
+        let mut crop = Crop::new(CropKind::Wheat, 0, 0, 100);
+        crop.advance(160);
+        assert_eq!(crop.growth, 60 * DRY_GROWTH);
+        assert!(!crop.is_watered(160));
+        // Going back in time never takes growth away
+        crop.advance(120);
+        assert_eq!(crop.growth, 60 * DRY_GROWTH);
+    }
+
+    #[test]
+    fn watered_crops_grow_faster() {
+        let mut crop = Crop::new(CropKind::Wheat, 0, 0, 0);
+        crop.water(0);
+        crop.advance(100);
+        assert_eq!(crop.growth, 100 * WATERED_GROWTH);
+        assert!(crop.is_watered(100));
+
+        // A full watered stage moves it on a stage
+        let stage_time = CropKind::Wheat.definition().stage_time;
+        let mut crop = Crop::new(CropKind::Wheat, 0, 0, 0);
+        crop.water(0);
+        crop.advance(stage_time);
+        assert_eq!(crop.stage(), 1);
+    }
+
+    #[test]
+    fn watering_runs_out_while_unloaded() {
+        // Watered, then left alone in an unloaded chunk far longer than the water lasts
+        let mut crop = Crop::new(CropKind::Carrot, 0, 0, 50);
+        crop.water(50);
+        let later = 50 + WATERED_FOR + 1000;
+        crop.advance(later);
+        assert_eq!(
+            crop.growth,
+            WATERED_FOR * WATERED_GROWTH + 1000 * DRY_GROWTH
+        );
+        assert!(!crop.is_watered(later));
+        assert!(crop.is_ripe());
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
+pub mod components;
+pub mod systems;
+
+use crate::crafting::CraftingScreen;
+use crate::inventory::InventoryScreen;
+use crate::procedural_generation::chunk_sprites::{spawn_chunk_sprites, ChunkSpritesPlugin};
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+pub use components::Crop;
+
+// Tilling grass into dirt, planting seeds & harvesting what grows
+// Crops are stored in their Chunk & grow off the world clock
+pub struct FarmingPlugin;
+
+impl Plugin for FarmingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ChunkSpritesPlugin::<components::CropsChanged>::default());
+        app.add_systems(
+            Update,
+            (systems::tend_crops, systems::till_and_plant)
+                .chain()
+                .run_if(in_state(InGame))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+        app.add_systems(
+            Update,
+            systems::grow_crops
+                .run_if(in_state(InGame))
+                .run_if(on_timer(Duration::from_secs(1))),
+        );
+        app.add_systems(
+            Update,
+            systems::catch_up_crops.before(spawn_chunk_sprites::<components::CropsChanged>),
+        );
+    }
+}
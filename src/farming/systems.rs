@@ -0,0 +1,229 @@
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+use rand::thread_rng;
+
+use crate::camera::CursorWorld;
+use crate::day_night::WorldClock;
+use crate::depth::Layer;
+use crate::farming::components::*;
+use crate::health::events::LootDropped;
+use crate::health::systems::roll_loot;
+use crate::inventory::Inventory;
+use crate::player::components::*;
+use crate::procedural_generation::chunk::{Chunk, TileType};
+use crate::procedural_generation::chunk_sprites::{ChunkSprites, SpriteSpawner};
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::props::prop_on_tile;
+use crate::procedural_generation::tile_map::TileMap;
+use crate::weather::{Weather, WeatherKind};
+
+// Furthest from the player a tile can be farmed
+const FARM_RANGE: f32 = 150.0;
+// Crop sprites are 16px
+const CROP_SCALE: f32 = 3.0;
+// Crops left dry are drawn faded
+const DRY_TINT: Color = Color::srgb(0.9, 0.85, 0.65);
+
+// Right click on a ripe crop harvests it, with the watering can it waters it instead
+pub fn tend_crops(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: CursorWorld,
+    player_query: Query<(&Transform, &EquippedTool), With<Player>>,
+    mut tile_map: TileMap,
+    clock: Res<WorldClock>,
+    mut dropped: EventWriter<LootDropped>,
+    mut changed: EventWriter<CropsChanged>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Ok((transform, tool)) = player_query.get_single() else {
+        return;
+    };
+    let Some(target) = cursor.position() else {
+        return;
+    };
+    if target.distance(transform.translation.truncate()) > FARM_RANGE {
+        return;
+    }
+    let (coord, column, row) = tile_in_chunk(tile_at(target));
+    let Some(chunk) = tile_map.chunk_mut(&coord) else {
+        return;
+    };
+    let Some(index) = chunk
+        .crops
+        .iter()
+        .position(|crop| crop.column == column && crop.row == row)
+    else {
+        return;
+    };
+
+    let now = clock.seconds();
+    let crop = &mut chunk.crops[index];
+    crop.advance(now);
+    if crop.is_ripe() {
+        let items = roll_loot(&crop.kind.definition().yields, &mut thread_rng());
+        chunk.crops.remove(index);
+        if !items.is_empty() {
+            dropped.send(LootDropped {
+                position: tile_position(&coord, column, row),
+                items,
+            });
+        }
+    } else if tool.0 == Some(Tool::WateringCan) {
+        crop.water(now);
+    } else {
+        return;
+    }
+    changed.send(CropsChanged(coord));
+}
+
+// Right click with a hoe tills grass, with seeds plants them on dirt
+pub fn till_and_plant(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: CursorWorld,
+    mut player_query: Query<(&Transform, &EquippedTool, &mut Inventory), With<Player>>,
+    mut tile_map: TileMap,
+    clock: Res<WorldClock>,
+    mut changed: EventWriter<CropsChanged>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Ok((transform, tool, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(target) = cursor.position() else {
+        return;
+    };
+    if target.distance(transform.translation.truncate()) > FARM_RANGE {
+        return;
+    }
+    let tile = tile_at(target);
+    let (coord, column, row) = tile_in_chunk(tile);
+    let Some(chunk) = tile_map.tiles().chunk(&coord) else {
+        return;
+    };
+    let occupied = prop_on_tile(chunk, &coord, column, row)
+        || chunk
+            .crops
+            .iter()
+            .any(|crop| crop.column == column && crop.row == row)
+        || chunk
+            .buildings
+            .iter()
+            .any(|building| building.column == column && building.row == row);
+    if occupied {
+        return;
+    }
+    let biome = chunk.biome;
+
+    if tool.0 == Some(Tool::Hoe) {
+        if tile_map.tiles().tile(tile) == Some(&TileType::Grass) {
+            tile_map.set_tile_at(tile, TileType::Dirt);
+        }
+        return;
+    }
+
+    let Some(kind) = inventory
+        .selected_stack()
+        .and_then(|stack| CropKind::from_seed(&stack.item))
+    else {
+        return;
+    };
+    if tile_map.tiles().tile(tile) != Some(&TileType::Dirt)
+        || !kind.definition().biomes.contains(&biome)
+    {
+        return;
+    }
+    let Some(chunk) = tile_map.chunk_mut(&coord) else {
+        return;
+    };
+    inventory.consume_selected();
+    chunk
+        .crops
+        .push(Crop::new(kind, column, row, clock.seconds()));
+    changed.send(CropsChanged(coord));
+}
+
+// Grows the crops of loaded chunks, rain waters them
+pub fn grow_crops(
+    mut rendered: ResMut<RenderedChunks>,
+    clock: Res<WorldClock>,
+    weather: Res<Weather>,
+    mut changed: EventWriter<CropsChanged>,
+) {
+    let now = clock.seconds();
+    let raining = weather.current == WeatherKind::Rain && weather.intensity > 0.5;
+    for (coord, chunk) in rendered.chunks.iter_mut() {
+        let mut redraw = false;
+        for crop in chunk.crops.iter_mut() {
+            let before = (crop.stage(), crop.is_watered(now));
+            if raining {
+                crop.water(now);
+            } else {
+                crop.advance(now);
+            }
+            redraw |= before != (crop.stage(), crop.is_watered(now));
+        }
+        if redraw {
+            changed.send(CropsChanged(coord.clone()));
+        }
+    }
+}
+
+// Crops in a chunk that was unloaded catch up on the time they missed
+pub fn catch_up_crops(
+    mut loaded: EventReader<ChunkLoaded>,
+    mut rendered: ResMut<RenderedChunks>,
+    clock: Res<WorldClock>,
+) {
+    let now = clock.seconds();
+    for ChunkLoaded(coord) in loaded.read() {
+        let Some(chunk) = rendered.chunks.get_mut(coord) else {
+            continue;
+        };
+        for crop in chunk.crops.iter_mut() {
+            crop.advance(now);
+        }
+    }
+}
+
+// Crops are respawned whenever they change how they look
+impl ChunkSprites for CropsChanged {
+    type Param = (Res<'static, WorldClock>, Res<'static, AssetServer>);
+
+    fn chunk(&self) -> &Coord {
+        &self.0
+    }
+
+    fn spawn(
+        sprites: &mut SpriteSpawner,
+        coord: &Coord,
+        chunk: &Chunk,
+        (clock, asset_server): &mut SystemParamItem<Self::Param>,
+    ) {
+        let now = clock.seconds();
+        for crop in chunk.crops.iter() {
+            let definition = crop.kind.definition();
+            let position = tile_position(coord, crop.column, crop.row);
+            let color = if crop.is_watered(now) {
+                Color::WHITE
+            } else {
+                DRY_TINT
+            };
+            sprites.spawn(SpriteBundle {
+                texture: asset_server.load(definition.stages[crop.stage()]),
+                sprite: Sprite {
+                    color,
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(
+                    position.extend(Layer::Decoration.z() + 0.25),
+                )
+                .with_scale(Vec3::splat(CROP_SCALE)),
+                ..Default::default()
+            });
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::day_night::DayPhase;
+use crate::procedural_generation::chunk::BiomeType;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+
+// Seconds between the line landing & a fish biting
+pub const BITE_TIME: (f32, f32) = (2.0, 8.0);
+// Seconds the player has to land a hooked fish
+pub const REEL_TIME: f32 = 5.0;
+
+pub struct CatchEntry {
+    pub item: &'static str,
+    pub weight: u32,
+    // Times of day it bites, empty bites at any time
+    pub phases: &'static [DayPhase],
+    // 0.0 - 1.0, harder fish have a smaller zone & a faster marker
+    pub difficulty: f32,
+}
+
+const fn entry(
+    item: &'static str,
+    weight: u32,
+    phases: &'static [DayPhase],
+    difficulty: f32,
+) -> CatchEntry {
+    CatchEntry {
+        item,
+        weight,
+        phases,
+        difficulty,
+    }
+}
+
+const TWILIGHT: &[DayPhase] = &[DayPhase::Dawn, DayPhase::Dusk];
+const NIGHT: &[DayPhase] = &[DayPhase::Night];
+const EVENING: &[DayPhase] = &[DayPhase::Dusk, DayPhase::Night];
+
+const OCEAN: &[CatchEntry] = &[
+    entry("cod", 50, &[], 0.3),
+    entry("salmon", 25, TWILIGHT, 0.65),
+    entry("eel", 15, NIGHT, 0.8),
+    entry("old_boot", 10, &[], 0.0),
+];
+const TUNDRA: &[CatchEntry] = &[
+    entry("char", 60, &[], 0.5),
+    entry("salmon", 20, TWILIGHT, 0.65),
+    entry("old_boot", 10, &[], 0.0),
+];
+const FOREST: &[CatchEntry] = &[
+    entry("trout", 60, &[], 0.35),
+    entry("catfish", 25, EVENING, 0.55),
+    entry("old_boot", 10, &[], 0.0),
+];
+const FRESHWATER: &[CatchEntry] = &[
+    entry("trout", 40, &[], 0.35),
+    entry("catfish", 30, NIGHT, 0.55),
+    entry("old_boot", 15, &[], 0.0),
+];
+
+// What can be caught in the water of a biome
+pub fn catch_table(biome: BiomeType) -> &'static [CatchEntry] {
+    match biome {
+        BiomeType::Ocean => OCEAN,
+        BiomeType::Tundra => TUNDRA,
+        BiomeType::Forest => FOREST,
+        _ => FRESHWATER,
+    }
+}
+
+// Picks by weight from the entries biting at this time of day
+pub fn roll_catch(
+    biome: BiomeType,
+    phase: DayPhase,
+    rng: &mut impl Rng,
+) -> Option<&'static CatchEntry> {
+    let biting: Vec<&CatchEntry> = catch_table(biome)
+        .iter()
+        .filter(|entry| entry.phases.is_empty() || entry.phases.contains(&phase))
+        .collect();
+    let total: u32 = biting.iter().map(|entry| entry.weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for entry in biting {
+        if roll < entry.weight {
+            return Some(entry);
+        }
+        roll -= entry.weight;
+    }
+    None
+}
+
+pub fn bite_time(rng: &mut impl Rng) -> f32 {
+    rng.gen_range(BITE_TIME.0..BITE_TIME.1)
+}
+
+// The timing minigame, a marker sweeps along a bar & the fish is landed by clicking while it's in the zone
+#[derive(Debug, Clone)]
+pub struct Reel {
+    // 0.0 - 1.0 along the bar
+    pub marker: f32,
+    // Bar lengths per second, negative going left
+    pub speed: f32,
+    pub zone_start: f32,
+    pub zone_width: f32,
+    pub time_left: f32,
+}
+
+impl Reel {
+    pub fn new(difficulty: f32, rng: &mut impl Rng) -> Self {
+        let zone_width = 0.35 - 0.25 * difficulty;
+        Self {
+            marker: 0.0,
+            speed: 0.8 + 1.2 * difficulty,
+            zone_start: rng.gen_range(0.0..=1.0 - zone_width),
+            zone_width,
+            time_left: REEL_TIME,
+        }
+    }
+
+    // Moves the marker, bouncing off the ends of the bar
+    pub fn tick(&mut self, seconds: f32) {
+        self.time_left -= seconds;
+        self.marker += self.speed * seconds;
+        if self.marker > 1.0 {
+            self.marker = 2.0 - self.marker;
+            self.speed = -self.speed;
+        } else if self.marker < 0.0 {
+            self.marker = -self.marker;
+            self.speed = -self.speed;
+        }
+        self.marker = self.marker.clamp(0.0, 1.0);
+    }
+
+    pub fn in_zone(&self) -> bool {
+        self.marker >= self.zone_start && self.marker <= self.zone_start + self.zone_width
+    }
+}
+
+// Reseeded from the world seed every cast, so the same world gives the same bites
+// The casts are counted & saved, so a reloaded game carries on instead of repeating its catches
+#[derive(Resource)]
+pub struct FishingRng {
+    seed: WorldSeed,
+    pub casts: u64,
+    pub rng: StdRng,
+}
+
+impl FishingRng {
+    pub fn new(seed: WorldSeed, casts: u64) -> Self {
+        Self {
+            seed,
+            casts,
+            rng: seed.rng(Pass::Fishing),
+        }
+    }
+
+    // The bite, catch & reel of a cast all come from the rng it starts
+    pub fn cast(&mut self) -> &mut StdRng {
+        let hash = self
+            .seed
+            .hash(self.casts as i32, (self.casts >> 32) as i32, Pass::Fishing);
+        self.rng = StdRng::seed_from_u64(hash);
+        self.casts += 1;
+        &mut self.rng
+    }
+}
+
+// Where the player is in a cast
+#[derive(Resource, Default)]
+pub enum Fishing {
+    #[default]
+    Idle,
+    Waiting {
+        bobber: Vec2,
+        biome: BiomeType,
+        bite_in: f32,
+    },
+    Hooked {
+        bobber: Vec2,
+        catch: &'static CatchEntry,
+        reel: Reel,
+    },
+}
+
+impl Fishing {
+    pub fn bobber(&self) -> Option<Vec2> {
+        match self {
+            Fishing::Idle => None,
+            Fishing::Waiting { bobber, .. } | Fishing::Hooked { bobber, .. } => Some(*bobber),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Bobber;
+
+#[derive(Component)]
+pub struct ReelBar;
+
+#[derive(Component)]
+pub struct ReelMarker;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Casts the way the fishing systems do, from the world's fishing rng: a bite, what bit & its reel
+    fn casts(seed: u64, from: u64, count: u64) -> Vec<(f32, &'static str, f32)> {
+        let mut rng = FishingRng::new(WorldSeed(seed), from);
+        (0..count)
+            .map(|_| {
+                let rng = rng.cast();
+                let bite = bite_time(rng);
+                let catch = roll_catch(BiomeType::Ocean, DayPhase::Day, rng).unwrap();
+                let reel = Reel::new(catch.difficulty, rng);
+                (bite, catch.item, reel.zone_start)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_world_seed_catches_the_same_fish() {
+        assert_eq!(casts(42, 0, 20), casts(42, 0, 20));
+        assert_ne!(casts(42, 0, 20), casts(43, 0, 20));
+    }
+
+    #[test]
+    fn saved_casts_carry_on_where_they_left_off() {
+        let all = casts(42, 0, 20);
+        assert_eq!(casts(42, 12, 8), all[12..]);
+        assert_ne!(casts(42, 12, 8), all[..8]);
+    }
+
+    #[test]
+    fn night_fish_only_bite_at_night() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let item = roll_catch(BiomeType::Ocean, DayPhase::Day, &mut rng)
+                .unwrap()
+                .item;
+            assert_ne!(item, "eel");
+        }
+        let caught_eel = (0..500).any(|_| {
+            roll_catch(BiomeType::Ocean, DayPhase::Night, &mut rng)
+                .unwrap()
+                .item
+                == "eel"
+        });
+        assert!(caught_eel);
+    }
+
+    #[test]
+    fn tables_follow_the_biome() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..200 {
+            let item = roll_catch(BiomeType::Tundra, DayPhase::Day, &mut rng)
+                .unwrap()
+                .item;
+            assert!(["char", "old_boot"].contains(&item));
+        }
+    }
+
+    #[test]
+    fn bites_come_within_the_bite_time() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let time = bite_time(&mut rng);
+            assert!((BITE_TIME.0..BITE_TIME.1).contains(&time));
+        }
+    }
+
+    #[test]
+    fn reel_marker_bounces_within_the_bar() {
+        let mut reel = Reel::new(1.0, &mut StdRng::seed_from_u64(5));
+        for _ in 0..200 {
+            reel.tick(0.07);
+            assert!((0.0..=1.0).contains(&reel.marker));
+        }
+        reel.marker = reel.zone_start + reel.zone_width / 2.0;
+        assert!(reel.in_zone());
+        reel.marker = if reel.zone_start > 0.5 { 0.0 } else { 1.0 };
+        assert!(!reel.in_zone());
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::crafting::CraftingScreen;
+use crate::inventory::InventoryScreen;
+use crate::saves::SaveSet;
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+// Casting into water with a rod, waiting for a bite & reeling the fish in
+pub struct FishingPlugin;
+
+impl Plugin for FishingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<components::Fishing>();
+        app.add_systems(OnEnter(InGame), systems::seed_fishing);
+        app.add_systems(OnExit(InGame), systems::stop_fishing);
+        app.add_systems(
+            Update,
+            (
+                systems::cast_line,
+                systems::wait_for_bite,
+                systems::reel_in,
+                systems::draw_fishing,
+            )
+                .chain()
+                .run_if(in_state(InGame))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+        app.add_systems(Last, systems::save_fishing.in_set(SaveSet::Collect));
+    }
+}
@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+
+use crate::camera::CursorWorld;
+use crate::day_night::WorldClock;
+use crate::depth::Layer;
+use crate::fishing::components::*;
+use crate::health::events::LootDropped;
+use crate::inventory::{Inventory, ItemRegistry};
+use crate::player::components::*;
+use crate::procedural_generation::chunk::TileType;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::seed::WorldSeed;
+use crate::saves::SaveData;
+
+// Furthest from the player the line can be cast
+const CAST_RANGE: f32 = 250.0;
+// Walking further than this from the bobber reels the line in
+const LINE_LENGTH: f32 = 350.0;
+const BOBBER_SCALE: f32 = 3.0;
+const BAR_WIDTH: f32 = 300.0;
+const BAR_HEIGHT: f32 = 20.0;
+const MARKER_WIDTH: f32 = 4.0;
+
+pub fn seed_fishing(mut commands: Commands, seed: Res<WorldSeed>, save: Res<SaveData>) {
+    let casts = save.get::<u64>("fishing").unwrap_or(0);
+    commands.insert_resource(FishingRng::new(*seed, casts));
+}
+
+pub fn save_fishing(rng: Option<Res<FishingRng>>, mut save: ResMut<SaveData>) {
+    if let Some(rng) = rng {
+        save.set("fishing", &rng.casts);
+    }
+}
+
+// Leaving the game drops whatever was on the line
+pub fn stop_fishing(mut fishing: ResMut<Fishing>) {
+    *fishing = Fishing::Idle;
+}
+
+// Right click with the rod casts at the water under the cursor, or pulls in a line still waiting for a bite
+pub fn cast_line(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: CursorWorld,
+    player_query: Query<(&Transform, &EquippedTool), With<Player>>,
+    rendered: Res<RenderedChunks>,
+    mut fishing: ResMut<Fishing>,
+    mut rng: ResMut<FishingRng>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    match *fishing {
+        Fishing::Waiting { .. } => {
+            *fishing = Fishing::Idle;
+            return;
+        }
+        Fishing::Hooked { .. } => return,
+        Fishing::Idle => {}
+    }
+    let Ok((transform, tool)) = player_query.get_single() else {
+        return;
+    };
+    if tool.0 != Some(Tool::FishingRod) {
+        return;
+    }
+    let Some(target) = cursor.position() else {
+        return;
+    };
+    if target.distance(transform.translation.truncate()) > CAST_RANGE {
+        return;
+    }
+    let (coord, column, row) = tile_in_chunk(tile_at(target));
+    let Some(chunk) = rendered.chunks.get(&coord) else {
+        return;
+    };
+    if chunk.tiles[row][column].tile_type != TileType::Water {
+        return;
+    }
+
+    *fishing = Fishing::Waiting {
+        bobber: tile_position(&coord, column, row),
+        biome: chunk.biome,
+        bite_in: bite_time(rng.cast()),
+    };
+}
+
+// Counts down to the bite, the fish on the line is decided by the biome & time of day
+pub fn wait_for_bite(
+    time: Res<Time>,
+    clock: Res<WorldClock>,
+    player_query: Query<&Transform, With<Player>>,
+    mut fishing: ResMut<Fishing>,
+    mut rng: ResMut<FishingRng>,
+) {
+    let Fishing::Waiting {
+        bobber,
+        biome,
+        bite_in,
+    } = &mut *fishing
+    else {
+        return;
+    };
+    let walked_off = player_query
+        .get_single()
+        .is_ok_and(|player| player.translation.truncate().distance(*bobber) > LINE_LENGTH);
+    if walked_off {
+        *fishing = Fishing::Idle;
+        return;
+    }
+
+    *bite_in -= time.delta_seconds();
+    if *bite_in > 0.0 {
+        return;
+    }
+    let bobber = *bobber;
+    *fishing = match roll_catch(*biome, clock.phase(), &mut rng.rng) {
+        Some(catch) => Fishing::Hooked {
+            bobber,
+            catch,
+            reel: Reel::new(catch.difficulty, &mut rng.rng),
+        },
+        None => Fishing::Idle,
+    };
+}
+
+// Right click while the marker is in the zone lands the fish, missing or running out of time loses it
+pub fn reel_in(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    items: Res<ItemRegistry>,
+    mut fishing: ResMut<Fishing>,
+    mut dropped: EventWriter<LootDropped>,
+) {
+    let Fishing::Hooked { catch, reel, .. } = &mut *fishing else {
+        return;
+    };
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        reel.tick(time.delta_seconds());
+        if reel.time_left <= 0.0 {
+            *fishing = Fishing::Idle;
+        }
+        return;
+    }
+
+    if reel.in_zone() {
+        if let Ok((transform, mut inventory)) = player_query.get_single_mut() {
+            let left = inventory.add(&items, catch.item, 1);
+            // A full inventory leaves the catch at the player's feet
+            if left > 0 {
+                dropped.send(LootDropped {
+                    position: transform.translation.truncate(),
+                    items: vec![(catch.item.to_string(), left)],
+                });
+            }
+        }
+    }
+    *fishing = Fishing::Idle;
+}
+
+// Keeps the bobber & the reel bar in line with the cast
+pub fn draw_fishing(
+    mut commands: Commands,
+    fishing: Res<Fishing>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut bobbers: Query<(Entity, &mut Transform), With<Bobber>>,
+    bars: Query<Entity, With<ReelBar>>,
+    mut markers: Query<&mut Style, With<ReelMarker>>,
+) {
+    match fishing.bobber() {
+        Some(position) => {
+            // Bobs gently while waiting & dips under once something bites
+            let offset = match *fishing {
+                Fishing::Hooked { .. } => -6.0 + (time.elapsed_seconds() * 20.0).sin() * 2.0,
+                _ => (time.elapsed_seconds() * 3.0).sin() * 2.0,
+            };
+            let translation = (position + Vec2::Y * offset).extend(Layer::Decoration.z() + 0.75);
+            if let Ok((_, mut transform)) = bobbers.get_single_mut() {
+                transform.translation = translation;
+            } else {
+                commands.spawn((
+                    SpriteBundle {
+                        texture: asset_server.load("sprites/fishing/bobber.png"),
+                        transform: Transform::from_translation(translation)
+                            .with_scale(Vec3::splat(BOBBER_SCALE)),
+                        ..Default::default()
+                    },
+                    Bobber,
+                ));
+            }
+        }
+        None => {
+            for (entity, _) in bobbers.iter() {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    let Fishing::Hooked { reel, .. } = &*fishing else {
+        for entity in bars.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+    let marker_left = Val::Px(reel.marker * (BAR_WIDTH - MARKER_WIDTH));
+    if let Ok(mut style) = markers.get_single_mut() {
+        style.left = marker_left;
+        return;
+    }
+    if !bars.is_empty() {
+        return;
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(120.0),
+                    left: Val::Percent(50.0),
+                    margin: UiRect::left(Val::Px(-BAR_WIDTH / 2.0)),
+                    width: Val::Px(BAR_WIDTH),
+                    height: Val::Px(BAR_HEIGHT),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
+                ..Default::default()
+            },
+            ReelBar,
+        ))
+        .with_children(|parent| {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(reel.zone_start * 100.0),
+                    width: Val::Percent(reel.zone_width * 100.0),
+                    height: Val::Percent(100.0),
+                    ..Default::default()
+                },
+                background_color: Color::srgb(0.25, 0.7, 0.3).into(),
+                ..Default::default()
+            });
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: marker_left,
+                        width: Val::Px(MARKER_WIDTH),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    background_color: Color::WHITE.into(),
+                    ..Default::default()
+                },
+                ReelMarker,
+            ));
+        });
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+pub mod systems;
+
+use crate::states::AppState;
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            (systems::spawn_game_over, systems::reset_player),
+        );
+        app.add_systems(OnExit(AppState::GameOver), systems::despawn_game_over);
+        app.add_systems(
+            Update,
+            systems::leave_game_over.run_if(in_state(AppState::GameOver)),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct GameOverScreen;
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use crate::game_over::GameOverScreen;
+use crate::player::components::DespawnedPlayer;
+use crate::states::AppState;
+use crate::DespawnedYet;
+
+pub fn spawn_game_over(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(30.0),
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..Default::default()
+            },
+            GameOverScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(ImageBundle {
+                style: Style {
+                    width: Val::Px(660.0),
+                    height: Val::Px(371.0),
+                    ..Default::default()
+                },
+                image: UiImage::new(asset_server.load("backdrops/loss_screen.png")),
+                ..Default::default()
+            });
+            parent.spawn(TextBundle::from_section(
+                "Press Enter to return to the menu",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+pub fn despawn_game_over(mut commands: Commands, query: Query<Entity, With<GameOverScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// A dead player starts over rather than being respawned where they died
+pub fn reset_player(
+    mut player_resource: ResMut<DespawnedPlayer>,
+    mut despawned: ResMut<NextState<DespawnedYet>>,
+) {
+    *player_resource = DespawnedPlayer::default();
+    despawned.set(DespawnedYet::No);
+}
+
+pub fn leave_game_over(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter) || keyboard_input.just_pressed(KeyCode::Escape) {
+        state.set(AppState::MainMenu);
+    }
+}
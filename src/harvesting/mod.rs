@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
+pub mod systems;
+
+use crate::crafting::CraftingScreen;
+use crate::inventory::InventoryScreen;
+use crate::states::AppState::*;
+use crate::world_map::MapScreen;
+
+// Chopping trees, mining rocks & stone tiles & picking bushes
+// Node state lives on the props in each Chunk, so it survives the chunk being unloaded
+pub struct HarvestPlugin;
+
+impl Plugin for HarvestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            systems::harvest
+                .run_if(in_state(InGame))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed)),
+        );
+        app.add_systems(
+            Update,
+            systems::mine
+                .run_if(in_state(InGame))
+                .run_if(in_state(MapScreen::Closed))
+                .run_if(in_state(InventoryScreen::Closed))
+                .run_if(in_state(CraftingScreen::Closed)),
+        );
+        app.add_systems(
+            Update,
+            systems::regrow_nodes
+                .run_if(in_state(InGame))
+                .run_if(on_timer(Duration::from_secs(1))),
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::camera::CursorWorld;
+use crate::day_night::WorldClock;
+use crate::health::components::LootTable;
+use crate::health::events::LootDropped;
+use crate::health::systems::roll_loot;
+use crate::player::components::*;
+use crate::procedural_generation::chunk::TileType;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::ores::OresChanged;
+use crate::procedural_generation::props::{loot, NodeRegistry, PropChanged};
+use crate::procedural_generation::tile_map::TileMap;
+
+// How close the player has to be to the foot of a node to hit it
+const HARVEST_RANGE: f32 = 110.0;
+// Furthest from the player a stone tile can be mined
+const MINE_RANGE: f32 = 150.0;
+// Pickaxe hits plain stone takes, ore takes the ore's hits
+const STONE_HITS: u32 = 3;
+
+// E hits the closest node in reach, once it has taken enough hits it drops its items & is used up
+pub fn harvest(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<(&Transform, &EquippedTool), With<Player>>,
+    mut rendered: ResMut<RenderedChunks>,
+    clock: Res<WorldClock>,
+    nodes: Res<NodeRegistry>,
+    mut dropped: EventWriter<LootDropped>,
+    mut changed: EventWriter<PropChanged>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    let Ok((player_transform, tool)) = player_query.get_single() else {
+        return;
+    };
+    let player = player_transform.translation.truncate();
+    let now = clock.seconds();
+
+    let mut nearest: Option<(Coord, usize, f32)> = None;
+    for (coord, chunk) in rendered.chunks.iter() {
+        let origin = chunk_origin(coord);
+        for (index, prop) in chunk.props.iter().enumerate() {
+            if nodes.get(prop.kind).is_none() || prop.is_depleted(now, &nodes) {
+                continue;
+            }
+            let distance = (origin + Vec2::new(prop.x as f32, prop.y as f32)).distance(player);
+            if distance <= HARVEST_RANGE && nearest.as_ref().is_none_or(|(_, _, d)| distance < *d) {
+                nearest = Some((coord.clone(), index, distance));
+            }
+        }
+    }
+    let Some((coord, index, _)) = nearest else {
+        return;
+    };
+    let Some(prop) = rendered
+        .chunks
+        .get_mut(&coord)
+        .and_then(|chunk| chunk.props.get_mut(index))
+    else {
+        return;
+    };
+    let Some(node) = nodes.get(prop.kind) else {
+        return;
+    };
+    // The wrong tool does nothing
+    if node.tool.is_some() && node.tool != tool.0 {
+        return;
+    }
+
+    prop.hits += 1;
+    if prop.hits < node.hits {
+        return;
+    }
+    prop.hits = 0;
+    prop.depleted_at = Some(now);
+
+    let items = roll_loot(&node.yields, &mut thread_rng());
+    if !items.is_empty() {
+        dropped.send(LootDropped {
+            position: chunk_origin(&coord) + Vec2::new(prop.x as f32, prop.y as f32),
+            items,
+        });
+    }
+    changed.send(PropChanged {
+        chunk: coord,
+        index,
+    });
+}
+
+// Only loaded chunks are checked, unloaded ones catch up when their props are next spawned
+pub fn regrow_nodes(
+    mut rendered: ResMut<RenderedChunks>,
+    clock: Res<WorldClock>,
+    nodes: Res<NodeRegistry>,
+    mut changed: EventWriter<PropChanged>,
+) {
+    // Nothing knows its regrow time until nodes.json has loaded
+    if nodes.0.is_empty() {
+        return;
+    }
+    let now = clock.seconds();
+    for (coord, chunk) in rendered.chunks.iter_mut() {
+        for (index, prop) in chunk.props.iter_mut().enumerate() {
+            if prop.depleted_at.is_some() && !prop.is_depleted(now, &nodes) {
+                prop.depleted_at = None;
+                changed.send(PropChanged {
+                    chunk: coord.clone(),
+                    index,
+                });
+            }
+        }
+    }
+}
+
+// Right click with a pickaxe chips at the stone tile under the cursor
+// Once broken it turns to gravel & drops its stone, along with the ore if there was a vein through it
+pub fn mine(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: CursorWorld,
+    player_query: Query<(&Transform, &EquippedTool), With<Player>>,
+    mut tile_map: TileMap,
+    mut dropped: EventWriter<LootDropped>,
+    mut changed: EventWriter<OresChanged>,
+    // The tile being mined & the hits it has taken
+    mut progress: Local<Option<(IVec2, u32)>>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Ok((transform, tool)) = player_query.get_single() else {
+        return;
+    };
+    if tool.0 != Some(Tool::Pickaxe) {
+        return;
+    }
+    let Some(target) = cursor.position() else {
+        return;
+    };
+    if target.distance(transform.translation.truncate()) > MINE_RANGE {
+        return;
+    }
+    let tile = tile_at(target);
+    if tile_map.tiles().tile(tile) != Some(&TileType::Stone) {
+        return;
+    }
+
+    let (coord, column, row) = tile_in_chunk(tile);
+    let ore = tile_map.tiles().chunk(&coord).and_then(|chunk| {
+        chunk
+            .ores
+            .iter()
+            .find(|ore| ore.column == column && ore.row == row)
+            .map(|ore| ore.kind)
+    });
+    let hits = match *progress {
+        Some((mining, hits)) if mining == tile => hits + 1,
+        _ => 1,
+    };
+    if hits < ore.map_or(STONE_HITS, |ore| ore.hits()) {
+        *progress = Some((tile, hits));
+        return;
+    }
+    *progress = None;
+
+    tile_map.set_tile_at(tile, TileType::Gravel);
+    let mut rng = thread_rng();
+    let mut items = roll_loot(&LootTable(vec![loot("stone", 1.0, 1, 2)]), &mut rng);
+    if let Some(ore) = ore {
+        items.push((ore.item().to_string(), rng.gen_range(1..=3)));
+        if let Some(chunk) = tile_map.chunk_mut(&coord) {
+            chunk
+                .ores
+                .retain(|vein| vein.column != column || vein.row != row);
+        }
+        changed.send(OresChanged(coord.clone()));
+    }
+    dropped.send(LootDropped {
+        position: tile_position(&coord, column, row),
+        items,
+    });
+}
@@ -0,0 +1,78 @@
+// Components shared by anything that can be damaged or killed
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::health::events::DamageType;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    // Health regained per second
+    pub regen: f32,
+}
+
+impl Health {
+    pub fn new(max: f32, regen: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            0.0
+        } else {
+            (self.current / self.max).clamp(0.0, 1.0)
+        }
+    }
+}
+
+// Fraction of each damage type that is ignored, 0.0 takes full damage & 1.0 is immune
+// Negative values make the actor weak to that damage type
+#[derive(Component, Debug, Clone, Default)]
+pub struct Resistances(pub HashMap<DamageType, f32>);
+
+impl Resistances {
+    pub fn with(mut self, damage_type: DamageType, resistance: f32) -> Self {
+        self.0.insert(damage_type, resistance);
+        self
+    }
+
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        self.0.get(&damage_type).copied().unwrap_or(0.0)
+    }
+}
+
+// Marks an actor whose health hit zero this frame, it is despawned at the end of the health systems
+#[derive(Component)]
+pub struct Dead;
+
+// Experience handed to whoever lands the killing blow
+#[derive(Component, Debug, Clone, Copy)]
+pub struct XpReward(pub u32);
+
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Experience {
+    pub total: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LootEntry {
+    pub item: String,
+    // Chance (0.0 - 1.0) that the entry drops at all
+    pub chance: f32,
+    pub min: u32,
+    pub max: u32,
+}
+
+#[derive(Component, Deserialize, Debug, Clone, Default)]
+pub struct LootTable(pub Vec<LootEntry>);
@@ -0,0 +1,42 @@
+// Events that drive the health pipeline
+
+use bevy::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Frost,
+    Poison,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub damage_type: DamageType,
+    // Whoever dealt the damage, used to award experience
+    pub source: Option<Entity>,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HealEvent {
+    pub target: Entity,
+    pub amount: f32,
+}
+
+// Sent once when an actor's health reaches zero
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Died {
+    pub entity: Entity,
+    pub killer: Option<Entity>,
+    pub position: Vec2,
+}
+
+// Sent when a dead actor rolls its loot table, a resource node is harvested or items don't fit
+// Each item lands in the world as a drop
+#[derive(Event, Debug, Clone)]
+pub struct LootDropped {
+    pub position: Vec2,
+    pub items: Vec<(String, u32)>,
+}
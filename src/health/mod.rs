@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod events;
+pub mod systems;
+
+use crate::states::AppState::*;
+
+// Health, damage and death for every actor (the player, enemies, ...)
+// None of this needs rendering, so it can run under MinimalPlugins
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<events::DamageEvent>();
+        app.add_event::<events::HealEvent>();
+        app.add_event::<events::Died>();
+        app.add_event::<events::LootDropped>();
+        app.add_systems(
+            Update,
+            (
+                systems::regenerate_health,
+                systems::apply_heals,
+                systems::apply_damage,
+                (systems::award_xp, systems::drop_loot, systems::player_death),
+                systems::despawn_dead,
+            )
+                .chain()
+                .in_set(HealthSet)
+                .run_if(in_state(InGame)),
+        );
+    }
+}
+
+// Everything that reads Died events should run after this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HealthSet;
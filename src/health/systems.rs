@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::health::components::*;
+use crate::health::events::*;
+use crate::player::components::Player;
+use crate::states::AppState;
+
+// Damage left after resistances, never negative
+pub fn resolve_damage(amount: f32, resistance: f32) -> f32 {
+    (amount * (1.0 - resistance.min(1.0))).max(0.0)
+}
+
+pub fn regenerate_health(mut query: Query<&mut Health, Without<Dead>>, time: Res<Time>) {
+    for mut health in query.iter_mut() {
+        if health.regen <= 0.0 || health.is_dead() || health.current >= health.max {
+            continue;
+        }
+        health.current = (health.current + health.regen * time.delta_seconds()).min(health.max);
+    }
+}
+
+pub fn apply_heals(mut heals: EventReader<HealEvent>, mut query: Query<&mut Health>) {
+    for heal in heals.read() {
+        if let Ok(mut health) = query.get_mut(heal.target) {
+            // The dead stay dead
+            if health.is_dead() {
+                continue;
+            }
+            health.current = (health.current + heal.amount.max(0.0)).min(health.max);
+        }
+    }
+}
+
+pub fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut query: Query<(&mut Health, Option<&Resistances>, Option<&Transform>)>,
+    mut died: EventWriter<Died>,
+) {
+    for damage in damage_events.read() {
+        let Ok((mut health, resistances, transform)) = query.get_mut(damage.target) else {
+            continue;
+        };
+        if health.is_dead() {
+            continue;
+        }
+
+        let resistance = resistances.map_or(0.0, |r| r.get(damage.damage_type));
+        health.current -= resolve_damage(damage.amount, resistance);
+
+        if health.is_dead() {
+            health.current = 0.0;
+            commands.entity(damage.target).insert(Dead);
+            died.send(Died {
+                entity: damage.target,
+                killer: damage.source,
+                position: transform.map_or(Vec2::ZERO, |t| t.translation.truncate()),
+            });
+        }
+    }
+}
+
+pub fn award_xp(
+    mut died: EventReader<Died>,
+    rewards: Query<&XpReward>,
+    mut experience: Query<&mut Experience>,
+) {
+    for event in died.read() {
+        let (Some(killer), Ok(reward)) = (event.killer, rewards.get(event.entity)) else {
+            continue;
+        };
+        if let Ok(mut experience) = experience.get_mut(killer) {
+            experience.total += reward.0;
+        }
+    }
+}
+
+pub fn drop_loot(
+    mut died: EventReader<Died>,
+    tables: Query<&LootTable>,
+    mut dropped: EventWriter<LootDropped>,
+) {
+    let mut rng = thread_rng();
+    for event in died.read() {
+        let Ok(table) = tables.get(event.entity) else {
+            continue;
+        };
+        let items = roll_loot(table, &mut rng);
+        if !items.is_empty() {
+            dropped.send(LootDropped {
+                position: event.position,
+                items,
+            });
+        }
+    }
+}
+
+pub fn roll_loot(table: &LootTable, rng: &mut impl Rng) -> Vec<(String, u32)> {
+    let mut items = Vec::new();
+    for entry in table.0.iter() {
+        if rng.gen::<f32>() >= entry.chance {
+            continue;
+        }
+        let count = rng.gen_range(entry.min..=entry.max.max(entry.min));
+        if count > 0 {
+            items.push((entry.item.clone(), count));
+        }
+    }
+    items
+}
+
+pub fn player_death(
+    mut died: EventReader<Died>,
+    player_query: Query<(), With<Player>>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    for event in died.read() {
+        if player_query.contains(event.entity) {
+            state.set(AppState::GameOver);
+        }
+    }
+}
+
+// The player is left alone, leaving InGame despawns it
+pub fn despawn_dead(mut commands: Commands, query: Query<Entity, (With<Dead>, Without<Player>)>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Just the damage & heal systems, no rendering or states
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_event::<DamageEvent>();
+        app.add_event::<HealEvent>();
+        app.add_event::<Died>();
+        app.add_systems(Update, (apply_heals, apply_damage).chain());
+        app
+    }
+
+    fn hit(app: &mut App, target: Entity, amount: f32, damage_type: DamageType) {
+        app.world_mut().send_event(DamageEvent {
+            target,
+            amount,
+            damage_type,
+            source: None,
+        });
+        app.update();
+    }
+
+    fn deaths(app: &App) -> Vec<Died> {
+        let events = app.world().resource::<Events<Died>>();
+        events.get_reader().read(events).copied().collect()
+    }
+
+    #[test]
+    fn resistance_scales_damage() {
+        assert_eq!(resolve_damage(10.0, 0.0), 10.0);
+        assert_eq!(resolve_damage(10.0, 0.25), 7.5);
+        assert_eq!(resolve_damage(10.0, 1.0), 0.0);
+        // Immunity is the most a resistance can do, weaknesses add damage
+        assert_eq!(resolve_damage(10.0, 3.0), 0.0);
+        assert_eq!(resolve_damage(10.0, -0.5), 15.0);
+    }
+
+    #[test]
+    fn loot_rolls_within_the_table() {
+        let table = LootTable(vec![
+            LootEntry {
+                item: "wood".to_string(),
+                chance: 1.0,
+                min: 2,
+                max: 4,
+            },
+            LootEntry {
+                item: "gem".to_string(),
+                chance: 0.0,
+                min: 1,
+                max: 1,
+            },
+        ]);
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..100 {
+            let items = roll_loot(&table, &mut rng);
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].0, "wood");
+            assert!((2..=4).contains(&items[0].1));
+        }
+    }
+
+    #[test]
+    fn damage_applies_resistances() {
+        let mut app = app();
+        let target = app
+            .world_mut()
+            .spawn((
+                Health::new(100.0, 0.0),
+                Resistances::default().with(DamageType::Fire, 0.5),
+            ))
+            .id();
+
+        hit(&mut app, target, 20.0, DamageType::Fire);
+        hit(&mut app, target, 20.0, DamageType::Physical);
+        assert_eq!(app.world().get::<Health>(target).unwrap().current, 70.0);
+        assert!(deaths(&app).is_empty());
+    }
+
+    #[test]
+    fn lethal_damage_kills_once() {
+        let mut app = app();
+        let target = app
+            .world_mut()
+            .spawn((Health::new(10.0, 0.0), Transform::from_xyz(3.0, 4.0, 0.0)))
+            .id();
+
+        hit(&mut app, target, 25.0, DamageType::Physical);
+        let health = app.world().get::<Health>(target).unwrap();
+        assert_eq!(health.current, 0.0);
+        assert!(app.world().get::<Dead>(target).is_some());
+        let died = deaths(&app);
+        assert_eq!(died.len(), 1);
+        assert_eq!(died[0].entity, target);
+        assert_eq!(died[0].position, Vec2::new(3.0, 4.0));
+
+        // Hitting a corpse doesn't kill it again
+        hit(&mut app, target, 25.0, DamageType::Physical);
+        assert_eq!(deaths(&app).len(), 1);
+    }
+
+    #[test]
+    fn the_dead_cant_be_healed() {
+        let mut app = app();
+        let target = app.world_mut().spawn(Health::new(10.0, 0.0)).id();
+
+        hit(&mut app, target, 4.0, DamageType::Physical);
+        app.world_mut().send_event(HealEvent {
+            target,
+            amount: 100.0,
+        });
+        app.update();
+        assert_eq!(app.world().get::<Health>(target).unwrap().current, 10.0);
+
+        hit(&mut app, target, 10.0, DamageType::Physical);
+        app.world_mut().send_event(HealEvent {
+            target,
+            amount: 5.0,
+        });
+        app.update();
+        assert!(app.world().get::<Health>(target).unwrap().is_dead());
+    }
+}
@@ -0,0 +1,47 @@
+// A number drawn with the digit sprites in assets/Numbers
+
+use bevy::prelude::*;
+
+use crate::hud::styles::DIGIT_SCALE;
+
+// Put this on a UI node, its children are rebuilt whenever the value changes
+#[derive(Component)]
+pub struct BitmapNumber(pub u32);
+
+pub fn calculate_digits(number: u32) -> Vec<u32> {
+    if number == 0 {
+        return vec![0];
+    }
+    let mut digits = Vec::new();
+    let mut number = number;
+    while number > 0 {
+        digits.push(number % 10);
+        number /= 10;
+    }
+    digits.reverse();
+    digits
+}
+
+pub fn render_bitmap_numbers(
+    mut commands: Commands,
+    query: Query<(Entity, &BitmapNumber), Changed<BitmapNumber>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, number) in query.iter() {
+        commands.entity(entity).despawn_descendants();
+        commands.entity(entity).with_children(|parent| {
+            for digit in calculate_digits(number.0) {
+                parent.spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(7.0 * DIGIT_SCALE),
+                        height: Val::Px(10.0 * DIGIT_SCALE),
+                        margin: UiRect::right(Val::Px(DIGIT_SCALE)),
+                        ..Default::default()
+                    },
+                    image: UiImage::new(asset_server.load(format!("Numbers/Number{}.png", digit))),
+                    ..Default::default()
+                });
+            }
+        });
+    }
+}
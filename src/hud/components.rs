@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Hud;
+
+#[derive(Component)]
+pub struct HealthBar;
+
+#[derive(Component)]
+pub struct ScoreCounter;
+
+#[derive(Component)]
+pub struct BiomeText;
+
+#[derive(Component)]
+pub struct CoordinateText;
+
+// One point for every enemy killed
+#[derive(Resource, Default)]
+pub struct Score(pub u32);
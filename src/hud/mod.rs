@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+pub mod bitmap_number;
+mod components;
+mod styles;
+pub mod systems;
+
+use crate::health::HealthSet;
+use crate::states::AppState::*;
+
+pub use components::Score;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>();
+        app.add_systems(OnEnter(InGame), systems::layout::spawn_hud);
+        app.add_systems(OnExit(InGame), systems::layout::despawn_hud);
+        app.add_systems(
+            Update,
+            (
+                systems::updates::count_kills.after(HealthSet),
+                systems::updates::update_health_bar,
+                systems::updates::update_score,
+                systems::updates::update_biome,
+                systems::updates::update_coordinates,
+            )
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(Update, bitmap_number::render_bitmap_numbers);
+    }
+}
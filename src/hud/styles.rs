@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+pub const BAR_WIDTH: f32 = 220.0;
+pub const BAR_HEIGHT: f32 = 18.0;
+pub const BAR_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+pub const HEALTH_COLOR: Color = Color::srgb(0.8, 0.15, 0.15);
+pub const TEXT_SIZE: f32 = 20.0;
+// Each digit sprite is 7x10 pixels, scaled up for the HUD
+pub const DIGIT_SCALE: f32 = 3.0;
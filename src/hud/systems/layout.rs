@@ -0,0 +1,97 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use crate::hud::bitmap_number::BitmapNumber;
+use crate::hud::components::*;
+use crate::hud::styles::*;
+
+// System
+pub fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>, score: Res<Score>) {
+    let mut hud = commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(16.0),
+                top: Val::Px(16.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Hud,
+    ));
+
+    add_bar(&mut hud, HEALTH_COLOR, HealthBar);
+    hud.with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            BitmapNumber(score.0),
+            ScoreCounter,
+        ));
+    });
+    add_text(&mut hud, &asset_server, BiomeText);
+    add_text(&mut hud, &asset_server, CoordinateText);
+}
+
+// System
+pub fn despawn_hud(mut commands: Commands, query: Query<Entity, With<Hud>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Not a system
+fn add_bar(parent: &mut EntityCommands, color: Color, marker: impl Component) {
+    parent.with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(BAR_WIDTH),
+                    height: Val::Px(BAR_HEIGHT),
+                    ..Default::default()
+                },
+                background_color: BAR_BACKGROUND_COLOR.into(),
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..Default::default()
+                        },
+                        background_color: color.into(),
+                        ..Default::default()
+                    },
+                    marker,
+                ));
+            });
+    });
+}
+
+// Not a system
+fn add_text(parent: &mut EntityCommands, asset_server: &Res<AssetServer>, marker: impl Component) {
+    parent.with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: TEXT_SIZE,
+                    color: Color::WHITE,
+                },
+            ),
+            marker,
+        ));
+    });
+}
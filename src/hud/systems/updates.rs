@@ -0,0 +1,86 @@
+// Each system only writes to the HUD when the value it shows has actually changed
+
+use bevy::prelude::*;
+
+use crate::health::components::Health;
+use crate::health::events::Died;
+use crate::hud::bitmap_number::BitmapNumber;
+use crate::hud::components::*;
+use crate::player::components::*;
+use crate::procedural_generation::map::{chunk_at, tile_at, RenderedChunks};
+
+pub fn count_kills(
+    mut died: EventReader<Died>,
+    player_query: Query<(), With<Player>>,
+    mut score: ResMut<Score>,
+) {
+    for event in died.read() {
+        if !player_query.contains(event.entity) {
+            score.0 += 1;
+        }
+    }
+}
+
+pub fn update_health_bar(
+    player_query: Query<&Health, With<Player>>,
+    mut bar_query: Query<&mut Style, With<HealthBar>>,
+) {
+    if let Ok(health) = player_query.get_single() {
+        set_bar(&mut bar_query, health.fraction());
+    }
+}
+
+fn set_bar<F: bevy::ecs::query::QueryFilter>(bar_query: &mut Query<&mut Style, F>, fraction: f32) {
+    let width = Val::Percent(fraction * 100.0);
+    for mut style in bar_query.iter_mut() {
+        if style.width != width {
+            style.width = width;
+        }
+    }
+}
+
+pub fn update_score(
+    score: Res<Score>,
+    mut counter_query: Query<&mut BitmapNumber, With<ScoreCounter>>,
+) {
+    for mut number in counter_query.iter_mut() {
+        if number.0 != score.0 {
+            number.0 = score.0;
+        }
+    }
+}
+
+pub fn update_biome(
+    player_query: Query<&Transform, With<Player>>,
+    rendered: Res<RenderedChunks>,
+    mut text_query: Query<&mut Text, With<BiomeText>>,
+) {
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    let Some(chunk) = rendered
+        .chunks
+        .get(&chunk_at(transform.translation.truncate()))
+    else {
+        return;
+    };
+    set_text(&mut text_query, format!("{:?}", chunk.biome));
+}
+
+pub fn update_coordinates(
+    player_query: Query<&Transform, With<Player>>,
+    mut text_query: Query<&mut Text, With<CoordinateText>>,
+) {
+    if let Ok(transform) = player_query.get_single() {
+        let tile = tile_at(transform.translation.truncate());
+        set_text(&mut text_query, format!("X: {} Y: {}", tile.x, tile.y));
+    }
+}
+
+fn set_text<F: bevy::ecs::query::QueryFilter>(text_query: &mut Query<&mut Text, F>, value: String) {
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::items::ItemRegistry;
+
+// The first HOTBAR_SLOTS slots are the hotbar
+pub const HOTBAR_SLOTS: usize = 9;
+pub const INVENTORY_SLOTS: usize = 36;
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InventoryScreen {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: &str, count: u32) -> Self {
+        Self {
+            item: item.to_string(),
+            count,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+    // Hotbar slot in the player's hand
+    pub selected: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new(INVENTORY_SLOTS)
+    }
+}
+
+impl Inventory {
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+            selected: 0,
+        }
+    }
+
+    // What a new character starts out with
+    pub fn starting() -> Self {
+        let mut inventory = Self::default();
+        inventory.slots[0] = Some(ItemStack::new("axe", 1));
+        inventory.slots[1] = Some(ItemStack::new("pickaxe", 1));
+        inventory
+    }
+
+    // Tops up existing stacks before filling empty slots
+    // Returns how many didn't fit
+    pub fn add(&mut self, registry: &ItemRegistry, item: &str, count: u32) -> u32 {
+        let max_stack = registry.max_stack(item);
+        let mut remaining = count;
+        if max_stack == 0 {
+            return remaining;
+        }
+
+        for stack in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+            if stack.item == item && stack.count < max_stack {
+                let moved = remaining.min(max_stack - stack.count);
+                stack.count += moved;
+                remaining -= moved;
+            }
+        }
+        for slot in self.slots.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let moved = remaining.min(max_stack);
+                *slot = Some(ItemStack::new(item, moved));
+                remaining -= moved;
+            }
+        }
+        remaining
+    }
+
+    pub fn count(&self, item: &str) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    // Takes `count` of an item, or nothing at all if there aren't enough
+    pub fn remove(&mut self, item: &str, count: u32) -> bool {
+        if self.count(item) < count {
+            return false;
+        }
+        let mut remaining = count;
+        // Emptied from the back, so the hotbar keeps its items the longest
+        for slot in self.slots.iter_mut().rev() {
+            if remaining == 0 {
+                break;
+            }
+            let Some(stack) = slot else {
+                continue;
+            };
+            if stack.item != item {
+                continue;
+            }
+            let taken = remaining.min(stack.count);
+            stack.count -= taken;
+            remaining -= taken;
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+        true
+    }
+
+    // Takes the whole stack out of a slot
+    pub fn take(&mut self, slot: usize) -> Option<ItemStack> {
+        self.slots.get_mut(slot)?.take()
+    }
+
+    // Takes the larger half of a stack, leaving the rest behind
+    pub fn split(&mut self, slot: usize) -> Option<ItemStack> {
+        let stack = self.slots.get_mut(slot)?.as_mut()?;
+        let taken = stack.count.div_ceil(2);
+        stack.count -= taken;
+        let split = ItemStack::new(&stack.item, taken);
+        if stack.count == 0 {
+            self.slots[slot] = None;
+        }
+        Some(split)
+    }
+
+    // Puts a held stack into a slot, merging with the same item or swapping with a different one
+    // Returns whatever is left in hand
+    pub fn place(
+        &mut self,
+        registry: &ItemRegistry,
+        slot: usize,
+        mut held: ItemStack,
+    ) -> Option<ItemStack> {
+        let Some(target) = self.slots.get_mut(slot) else {
+            return Some(held);
+        };
+        match target {
+            None => {
+                let max_stack = registry.max_stack(&held.item).max(1);
+                let moved = held.count.min(max_stack);
+                *target = Some(ItemStack::new(&held.item, moved));
+                held.count -= moved;
+                (held.count > 0).then_some(held)
+            }
+            Some(stack) if stack.item == held.item => {
+                let max_stack = registry.max_stack(&held.item);
+                let moved = held.count.min(max_stack.saturating_sub(stack.count));
+                stack.count += moved;
+                held.count -= moved;
+                (held.count > 0).then_some(held)
+            }
+            Some(_) => target.replace(held),
+        }
+    }
+
+    // Puts a single item from the held stack into a slot
+    pub fn place_one(
+        &mut self,
+        registry: &ItemRegistry,
+        slot: usize,
+        mut held: ItemStack,
+    ) -> Option<ItemStack> {
+        // Never swaps, a different item in the slot just leaves the held stack alone
+        let fits = match self.slots.get(slot) {
+            Some(None) => true,
+            Some(Some(stack)) => {
+                stack.item == held.item && stack.count < registry.max_stack(&held.item)
+            }
+            None => false,
+        };
+        if !fits {
+            return Some(held);
+        }
+        self.place(registry, slot, ItemStack::new(&held.item, 1));
+        held.count -= 1;
+        (held.count > 0).then_some(held)
+    }
+
+    // Uses up one of the item in hand, returning its id
+    pub fn consume_selected(&mut self) -> Option<String> {
+        let slot = self.slots.get_mut(self.selected)?;
+        let stack = slot.as_mut()?;
+        let item = stack.item.clone();
+        stack.count -= 1;
+        if stack.count == 0 {
+            *slot = None;
+        }
+        Some(item)
+    }
+
+    pub fn selected_stack(&self) -> Option<&ItemStack> {
+        self.slots.get(self.selected)?.as_ref()
+    }
+}
+
+// The stack being dragged around the inventory screen
+#[derive(Resource, Debug, Default)]
+pub struct HeldStack {
+    pub stack: Option<ItemStack>,
+    // Slot the stack was picked up from, so a drag onto another slot drops it there
+    pub from: Option<usize>,
+}
+
+// === UI ===
+
+#[derive(Component)]
+pub struct Hotbar;
+
+#[derive(Component)]
+pub struct InventoryPanel;
+
+// A slot in the hotbar or inventory screen, showing Inventory::slots[index]
+#[derive(Component)]
+pub struct SlotButton(pub usize);
+
+#[derive(Component)]
+pub struct HeldIcon;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::items::{ItemCategory, ItemDefinition};
+
+    fn registry() -> ItemRegistry {
+        let item = |id: &str, max_stack| ItemDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            icon: String::new(),
+            max_stack,
+            category: ItemCategory::Material,
+            tool: None,
+            station: None,
+            layer: Default::default(),
+            door: false,
+        };
+        ItemRegistry::from_definitions(vec![item("wood", 10), item("stone", 10), item("axe", 1)])
+    }
+
+    #[test]
+    fn add_tops_up_existing_stacks_first() {
+        let registry = registry();
+        let mut inventory = Inventory::new(4);
+        inventory.slots[2] = Some(ItemStack::new("wood", 7));
+
+        assert_eq!(inventory.add(&registry, "wood", 5), 0);
+        assert_eq!(inventory.slots[2], Some(ItemStack::new("wood", 10)));
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("wood", 2)));
+    }
+
+    #[test]
+    fn add_splits_across_slots_at_max_stack() {
+        let registry = registry();
+        let mut inventory = Inventory::new(4);
+
+        assert_eq!(inventory.add(&registry, "wood", 25), 0);
+        assert_eq!(inventory.count("wood"), 25);
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("wood", 10)));
+        assert_eq!(inventory.slots[1], Some(ItemStack::new("wood", 10)));
+        assert_eq!(inventory.slots[2], Some(ItemStack::new("wood", 5)));
+    }
+
+    #[test]
+    fn add_returns_overflow_when_full() {
+        let registry = registry();
+        let mut inventory = Inventory::new(2);
+        inventory.slots[0] = Some(ItemStack::new("stone", 10));
+
+        assert_eq!(inventory.add(&registry, "wood", 15), 5);
+        assert_eq!(inventory.count("wood"), 10);
+        assert_eq!(inventory.add(&registry, "stone", 1), 1);
+    }
+
+    #[test]
+    fn unstackable_items_take_a_slot_each() {
+        let registry = registry();
+        let mut inventory = Inventory::new(3);
+
+        assert_eq!(inventory.add(&registry, "axe", 2), 0);
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("axe", 1)));
+        assert_eq!(inventory.slots[1], Some(ItemStack::new("axe", 1)));
+    }
+
+    #[test]
+    fn unknown_items_are_rejected() {
+        let registry = registry();
+        let mut inventory = Inventory::new(3);
+
+        assert_eq!(inventory.add(&registry, "mystery", 3), 3);
+        assert!(inventory.slots.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn remove_is_all_or_nothing() {
+        let registry = registry();
+        let mut inventory = Inventory::new(4);
+        inventory.add(&registry, "wood", 12);
+
+        assert!(!inventory.remove("wood", 13));
+        assert_eq!(inventory.count("wood"), 12);
+        assert!(inventory.remove("wood", 12));
+        assert!(inventory.slots.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn split_takes_the_larger_half() {
+        let mut inventory = Inventory::new(2);
+        inventory.slots[0] = Some(ItemStack::new("wood", 7));
+
+        assert_eq!(inventory.split(0), Some(ItemStack::new("wood", 4)));
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("wood", 3)));
+
+        inventory.slots[1] = Some(ItemStack::new("wood", 1));
+        assert_eq!(inventory.split(1), Some(ItemStack::new("wood", 1)));
+        assert_eq!(inventory.slots[1], None);
+    }
+
+    #[test]
+    fn place_merges_up_to_max_stack() {
+        let registry = registry();
+        let mut inventory = Inventory::new(2);
+        inventory.slots[0] = Some(ItemStack::new("wood", 8));
+
+        let leftover = inventory.place(&registry, 0, ItemStack::new("wood", 5));
+        assert_eq!(leftover, Some(ItemStack::new("wood", 3)));
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("wood", 10)));
+    }
+
+    #[test]
+    fn place_swaps_different_items() {
+        let registry = registry();
+        let mut inventory = Inventory::new(2);
+        inventory.slots[0] = Some(ItemStack::new("stone", 2));
+
+        let leftover = inventory.place(&registry, 0, ItemStack::new("wood", 5));
+        assert_eq!(leftover, Some(ItemStack::new("stone", 2)));
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("wood", 5)));
+    }
+
+    #[test]
+    fn place_one_leaves_the_rest_in_hand() {
+        let registry = registry();
+        let mut inventory = Inventory::new(2);
+
+        let leftover = inventory.place_one(&registry, 1, ItemStack::new("wood", 3));
+        assert_eq!(leftover, Some(ItemStack::new("wood", 2)));
+        assert_eq!(inventory.slots[1], Some(ItemStack::new("wood", 1)));
+
+        inventory.slots[0] = Some(ItemStack::new("stone", 1));
+        let leftover = inventory.place_one(&registry, 0, ItemStack::new("wood", 2));
+        assert_eq!(leftover, Some(ItemStack::new("wood", 2)));
+    }
+}
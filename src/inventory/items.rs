@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::building::BuildingLayer;
+use crate::crafting::Station;
+use crate::data::DataRegistry;
+use crate::player::components::Tool;
+
+pub const ITEMS_PATH: &str = "items.json";
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+    Material,
+    Food,
+    Tool,
+    Seed,
+    Placeable,
+}
+
+// One entry of assets/items.json
+#[derive(Deserialize, Debug, Clone)]
+pub struct ItemDefinition {
+    pub id: String,
+    pub name: String,
+    // Path of the icon, relative to assets
+    pub icon: String,
+    pub max_stack: u32,
+    pub category: ItemCategory,
+    // Holding the item equips this tool
+    #[serde(default)]
+    pub tool: Option<Tool>,
+    // Placing the item builds this crafting station
+    #[serde(default)]
+    pub station: Option<Station>,
+    // Where a placeable item goes when it's built
+    #[serde(default)]
+    pub layer: BuildingLayer,
+    // Built as a door, which F opens & shuts
+    #[serde(default)]
+    pub door: bool,
+}
+
+// The whole of assets/items.json
+#[derive(Asset, TypePath, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct ItemList(pub Vec<ItemDefinition>);
+
+// Every item the game knows about, keyed by id
+// Empty until items.json has loaded
+#[derive(Resource, Debug, Default)]
+pub struct ItemRegistry(pub HashMap<String, ItemDefinition>);
+
+impl ItemRegistry {
+    pub fn from_definitions(definitions: Vec<ItemDefinition>) -> Self {
+        Self(
+            definitions
+                .into_iter()
+                .map(|definition| (definition.id.clone(), definition))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ItemDefinition> {
+        self.0.get(id)
+    }
+
+    // Unknown items can't be stacked or stored at all
+    pub fn max_stack(&self, id: &str) -> u32 {
+        self.get(id).map(|item| item.max_stack).unwrap_or(0)
+    }
+}
+
+impl DataRegistry for ItemRegistry {
+    type Data = ItemList;
+    type Param = ();
+
+    fn reload(&mut self, _name: &str, list: &ItemList, _param: &mut ()) {
+        *self = ItemRegistry::from_definitions(list.0.clone());
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod items;
+mod styles;
+pub mod systems;
+
+use crate::data::DataFileApp;
+use crate::saves::SaveSet;
+use crate::states::AppState::*;
+
+pub use components::{Inventory, InventoryScreen, ItemStack};
+pub use items::ItemRegistry;
+
+// The player's items, the hotbar & the inventory screen (I or Tab)
+// Items are described in assets/items.json
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<InventoryScreen>();
+        app.init_resource::<components::HeldStack>();
+        app.add_data_file::<ItemRegistry>(items::ITEMS_PATH, &["json"]);
+        app.add_systems(Startup, systems::saving::load_inventory);
+        app.add_systems(OnEnter(InGame), systems::layout::spawn_hotbar);
+        app.add_systems(
+            OnExit(InGame),
+            (
+                systems::layout::despawn_hotbar,
+                systems::interaction::force_close,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                systems::interaction::toggle_inventory,
+                systems::interaction::select_hotbar,
+                systems::interaction::click_slots.run_if(in_state(InventoryScreen::Open)),
+                systems::interaction::equip_selected,
+                systems::layout::refresh_slots,
+                systems::interaction::follow_cursor,
+            )
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(
+            OnEnter(InventoryScreen::Open),
+            systems::layout::open_inventory,
+        );
+        app.add_systems(
+            OnExit(InventoryScreen::Open),
+            (
+                systems::interaction::return_held,
+                systems::layout::close_inventory,
+            ),
+        );
+        app.add_systems(
+            Last,
+            systems::saving::save_inventory.in_set(SaveSet::Collect),
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+pub const SLOT_SIZE: f32 = 52.0;
+pub const SLOT_GAP: f32 = 4.0;
+pub const ICON_SIZE: f32 = 36.0;
+pub const COUNT_SIZE: f32 = 14.0;
+pub const SLOT_COLOR: Color = Color::srgba(0.1, 0.1, 0.12, 0.75);
+pub const SELECTED_SLOT_COLOR: Color = Color::srgba(0.85, 0.7, 0.3, 0.9);
+pub const PANEL_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+pub const FONT: &str = "fonts/FiraMono-Medium.ttf";
@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::health::events::LootDropped;
+use crate::inventory::components::*;
+use crate::inventory::items::ItemRegistry;
+use crate::inventory::styles::ICON_SIZE;
+use crate::player::components::*;
+
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+pub fn toggle_inventory(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    current: Res<State<InventoryScreen>>,
+    mut next: ResMut<NextState<InventoryScreen>>,
+) {
+    if keyboard_input.any_just_pressed([KeyCode::KeyI, KeyCode::Tab]) {
+        next.set(match current.get() {
+            InventoryScreen::Closed => InventoryScreen::Open,
+            InventoryScreen::Open => InventoryScreen::Closed,
+        });
+    }
+}
+
+// Leaving the game always closes the inventory
+pub fn force_close(mut next: ResMut<NextState<InventoryScreen>>) {
+    next.set(InventoryScreen::Closed);
+}
+
+// Number keys pick the hotbar slot in the player's hand
+pub fn select_hotbar(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut Inventory, With<Player>>,
+) {
+    let Some(slot) = HOTBAR_KEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+    else {
+        return;
+    };
+    let Ok(mut inventory) = query.get_single_mut() else {
+        return;
+    };
+    if inventory.selected != slot {
+        inventory.selected = slot;
+    }
+}
+
+// Holding a tool item equips that tool
+pub fn equip_selected(
+    registry: Res<ItemRegistry>,
+    mut query: Query<(Ref<Inventory>, &mut EquippedTool), With<Player>>,
+) {
+    for (inventory, mut tool) in query.iter_mut() {
+        if !inventory.is_changed() {
+            continue;
+        }
+        let selected = inventory
+            .selected_stack()
+            .and_then(|stack| registry.get(&stack.item))
+            .and_then(|item| item.tool);
+        if tool.0 != selected {
+            tool.0 = selected;
+        }
+    }
+}
+
+// Left click picks up or puts down a whole stack, right click splits a stack or puts down one item
+// Dragging a stack onto another slot & letting go drops it there
+pub fn click_slots(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    slots: Query<(&SlotButton, &Node, &GlobalTransform)>,
+    registry: Res<ItemRegistry>,
+    mut held: ResMut<HeldStack>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let Some(slot) = slots
+        .iter()
+        .find(|(_, node, transform)| node.logical_rect(transform).contains(cursor))
+        .map(|(slot, _, _)| slot.0)
+    else {
+        return;
+    };
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        match held.stack.take() {
+            Some(stack) => {
+                held.stack = inventory.place(&registry, slot, stack);
+                held.from = None;
+            }
+            None => {
+                held.stack = inventory.take(slot);
+                held.from = Some(slot);
+            }
+        }
+    } else if mouse_input.just_pressed(MouseButton::Right) {
+        match held.stack.take() {
+            Some(stack) => {
+                held.stack = inventory.place_one(&registry, slot, stack);
+                held.from = None;
+            }
+            None => {
+                held.stack = inventory.split(slot);
+                held.from = Some(slot);
+            }
+        }
+    } else if mouse_input.just_released(MouseButton::Left) {
+        // Letting go over the slot it came from keeps holding it, so clicking works as well as dragging
+        if held.from.is_some_and(|from| from != slot) {
+            if let Some(stack) = held.stack.take() {
+                held.stack = inventory.place(&registry, slot, stack);
+            }
+            held.from = None;
+        }
+    }
+}
+
+pub fn follow_cursor(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    held: Res<HeldStack>,
+    registry: Res<ItemRegistry>,
+    asset_server: Res<AssetServer>,
+    mut icon_query: Query<(&mut Style, &mut UiImage, &mut Visibility), With<HeldIcon>>,
+) {
+    let Ok((mut style, mut image, mut visibility)) = icon_query.get_single_mut() else {
+        return;
+    };
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let item = held
+        .stack
+        .as_ref()
+        .and_then(|stack| registry.get(&stack.item));
+
+    let (Some(cursor), Some(item)) = (cursor, item) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Visible;
+    style.left = Val::Px(cursor.x - ICON_SIZE / 2.0);
+    style.top = Val::Px(cursor.y - ICON_SIZE / 2.0);
+    if held.is_changed() {
+        image.texture = asset_server.load(item.icon.clone());
+    }
+}
+
+// Closing the screen puts the held stack back, anything that doesn't fit is dropped at the player's feet
+pub fn return_held(
+    mut held: ResMut<HeldStack>,
+    registry: Res<ItemRegistry>,
+    mut player_query: Query<(&mut Inventory, &Transform), With<Player>>,
+    mut dropped: EventWriter<LootDropped>,
+) {
+    held.from = None;
+    let Some(stack) = held.stack.take() else {
+        return;
+    };
+    let Ok((mut inventory, transform)) = player_query.get_single_mut() else {
+        return;
+    };
+    let overflow = inventory.add(&registry, &stack.item, stack.count);
+    if overflow > 0 {
+        dropped.send(LootDropped {
+            position: transform.translation.truncate(),
+            items: vec![(stack.item, overflow)],
+        });
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+
+use crate::inventory::components::*;
+use crate::inventory::items::ItemRegistry;
+use crate::inventory::styles::*;
+use crate::player::components::Player;
+
+// The hotbar stays on screen the whole time in game
+pub fn spawn_hotbar(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(16.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Hotbar,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(SLOT_GAP),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for index in 0..HOTBAR_SLOTS {
+                        spawn_slot(parent, index);
+                    }
+                });
+        });
+}
+
+pub fn despawn_hotbar(mut commands: Commands, query: Query<Entity, With<Hotbar>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// The rest of the slots, in a grid above the hotbar
+pub fn open_inventory(mut commands: Commands) {
+    let columns = HOTBAR_SLOTS;
+    let rows = (INVENTORY_SLOTS - HOTBAR_SLOTS).div_ceil(columns);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(5),
+                ..Default::default()
+            },
+            InventoryPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_template_columns: RepeatedGridTrack::px(columns as u16, SLOT_SIZE),
+                        grid_template_rows: RepeatedGridTrack::px(rows as u16, SLOT_SIZE),
+                        row_gap: Val::Px(SLOT_GAP),
+                        column_gap: Val::Px(SLOT_GAP),
+                        padding: UiRect::all(Val::Px(12.0)),
+                        ..Default::default()
+                    },
+                    background_color: PANEL_COLOR.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for index in HOTBAR_SLOTS..INVENTORY_SLOTS {
+                        spawn_slot(parent, index);
+                    }
+                });
+        });
+
+    commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Px(ICON_SIZE),
+                height: Val::Px(ICON_SIZE),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(20),
+            ..Default::default()
+        },
+        HeldIcon,
+    ));
+}
+
+// Everything spawned by open_inventory
+type InventoryUi = Or<(With<InventoryPanel>, With<HeldIcon>)>;
+
+pub fn close_inventory(mut commands: Commands, query: Query<Entity, InventoryUi>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Not a system
+fn spawn_slot(parent: &mut ChildBuilder, index: usize) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(SLOT_SIZE),
+                height: Val::Px(SLOT_SIZE),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            background_color: SLOT_COLOR.into(),
+            ..Default::default()
+        },
+        SlotButton(index),
+    ));
+}
+
+// Rebuilds the slot contents whenever the inventory changes or new slots are spawned
+pub fn refresh_slots(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<ItemRegistry>,
+    player_query: Query<Ref<Inventory>, With<Player>>,
+    mut slots: Query<(Entity, &SlotButton, &mut BackgroundColor)>,
+    added: Query<(), Added<SlotButton>>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    if !inventory.is_changed() && added.is_empty() {
+        return;
+    }
+
+    for (entity, slot, mut background) in slots.iter_mut() {
+        *background = if slot.0 == inventory.selected {
+            SELECTED_SLOT_COLOR.into()
+        } else {
+            SLOT_COLOR.into()
+        };
+
+        commands.entity(entity).despawn_descendants();
+        let Some(stack) = inventory.slots.get(slot.0).and_then(Option::as_ref) else {
+            continue;
+        };
+        let Some(item) = registry.get(&stack.item) else {
+            continue;
+        };
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn(ImageBundle {
+                style: Style {
+                    width: Val::Px(ICON_SIZE),
+                    height: Val::Px(ICON_SIZE),
+                    ..Default::default()
+                },
+                image: UiImage::new(asset_server.load(item.icon.clone())),
+                ..Default::default()
+            });
+            if stack.count > 1 {
+                parent.spawn(
+                    TextBundle::from_section(
+                        stack.count.to_string(),
+                        TextStyle {
+                            font: asset_server.load(FONT),
+                            font_size: COUNT_SIZE,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        right: Val::Px(3.0),
+                        bottom: Val::Px(1.0),
+                        ..Default::default()
+                    }),
+                );
+            }
+        });
+    }
+}
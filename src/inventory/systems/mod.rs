@@ -0,0 +1,3 @@
+pub mod interaction;
+pub mod layout;
+pub mod saving;
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::inventory::components::Inventory;
+use crate::player::components::*;
+use crate::saves::SaveData;
+
+// The saved inventory goes into DespawnedPlayer, which the player is spawned from
+pub fn load_inventory(save: Res<SaveData>, mut player_resource: ResMut<DespawnedPlayer>) {
+    if let Some(inventory) = save.get::<Inventory>("inventory") {
+        player_resource.inventory = inventory;
+    }
+}
+
+pub fn save_inventory(
+    player_query: Query<&Inventory, With<Player>>,
+    player_resource: Res<DespawnedPlayer>,
+    mut save: ResMut<SaveData>,
+) {
+    let inventory = player_query
+        .get_single()
+        .unwrap_or(&player_resource.inventory);
+    save.set("inventory", inventory);
+}
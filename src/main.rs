@@ -18,7 +18,64 @@ mod camera;
 
 mod procedural_generation;
 
+mod enemy;
+
+mod combat;
+
+mod health;
+
+mod game_over;
+
+mod hud;
+
+mod minimap;
+
+mod saves;
+
+mod world_map;
+
+mod day_night;
+
+mod weather;
+
+mod animation;
+
+mod data;
+
+mod depth;
+
+mod harvesting;
+
+mod inventory;
+
+mod crafting;
+
+mod building;
+
+mod drops;
+
+mod farming;
+
+mod fishing;
+
+mod dungeon;
+
+mod tiled;
+
 fn main() {
+    // `export-tmx` writes part of a generated world out for Tiled instead of starting the game
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(result) = tiled::export::run_from_args(&args) {
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     bevy::app::App::new()
         //
         // === Plugins ===
@@ -27,6 +84,27 @@ fn main() {
         .add_plugins(camera::CameraPlugin)
         .add_plugins(player::PlayerPlugin)
         .add_plugins(procedural_generation::ProceduralGenerationPlugin)
+        .add_plugins(enemy::EnemyPlugin)
+        .add_plugins(combat::CombatPlugin)
+        .add_plugins(health::HealthPlugin)
+        .add_plugins(game_over::GameOverPlugin)
+        .add_plugins(hud::HudPlugin)
+        .add_plugins(minimap::MinimapPlugin)
+        .add_plugins(saves::SavePlugin)
+        .add_plugins(world_map::WorldMapPlugin)
+        .add_plugins(day_night::DayNightPlugin { day_length: 600.0 })
+        .add_plugins(weather::WeatherPlugin)
+        .add_plugins(animation::AnimationPlugin)
+        .add_plugins(depth::DepthPlugin)
+        .add_plugins(harvesting::HarvestPlugin)
+        .add_plugins(inventory::InventoryPlugin)
+        .add_plugins(crafting::CraftingPlugin)
+        .add_plugins(building::BuildingPlugin)
+        .add_plugins(drops::DropsPlugin)
+        .add_plugins(farming::FarmingPlugin)
+        .add_plugins(fishing::FishingPlugin)
+        .add_plugins(dungeon::DungeonPlugin)
+        .add_plugins(tiled::TiledPlugin)
         //
         // === Resources ===
         .insert_state(MainMenu)
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Minimap;
+
+// Rotated in MinimapMode::Rotate, the image hangs off it so the player sits on its centre
+#[derive(Component)]
+pub struct MinimapPivot;
+
+#[derive(Component)]
+pub struct MinimapView;
+
+#[derive(Component)]
+pub struct PlayerMarker;
+
+// The CPU side image the chunks are painted into
+#[derive(Resource)]
+pub struct MinimapImage(pub Handle<Image>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MinimapMode {
+    #[default]
+    NorthUp,
+    // The player's heading always points up
+    Rotate,
+}
+
+#[derive(Resource)]
+pub struct MinimapSettings {
+    pub mode: MinimapMode,
+    // Screen pixels per tile
+    pub scale: f32,
+    // Width & height of the minimap frame in pixels
+    pub size: f32,
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        Self {
+            mode: MinimapMode::NorthUp,
+            scale: 3.0,
+            size: 180.0,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+mod components;
+pub mod systems;
+
+use crate::states::AppState::*;
+
+pub use components::{MinimapMode, MinimapSettings};
+
+// A corner minimap drawn straight from chunk data, one pixel per tile
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinimapSettings>();
+        app.add_systems(Startup, systems::create_minimap_image);
+        app.add_systems(OnEnter(InGame), systems::spawn_minimap);
+        app.add_systems(OnExit(InGame), systems::despawn_minimap);
+        app.add_systems(
+            Update,
+            (
+                systems::paint_chunks,
+                systems::toggle_mode,
+                systems::follow_player,
+            )
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+    }
+}
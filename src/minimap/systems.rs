@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::minimap::components::*;
+use crate::player::components::Player;
+use crate::procedural_generation::chunk::{Chunk, TileType, CHUNK_SIZE};
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::tile_map::TileChanged;
+
+const BACKGROUND: [u8; 4] = [0, 0, 0, 0];
+
+pub fn tile_color(tile_type: &TileType) -> [u8; 4] {
+    match tile_type {
+        TileType::Grass => [76, 153, 60, 255],
+        TileType::Sand => [222, 204, 130, 255],
+        TileType::Snow => [240, 244, 250, 255],
+        TileType::Stone => [128, 128, 136, 255],
+        TileType::Water => [52, 101, 184, 255],
+        TileType::Dirt => [120, 82, 50, 255],
+        TileType::Gravel => [112, 104, 96, 255],
+    }
+}
+
+pub fn create_minimap_image(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: WORLD_TILES as u32,
+            height: WORLD_TILES as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &BACKGROUND,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // Keep every tile a crisp square when scaled up
+    image.sampler = ImageSampler::nearest();
+    commands.insert_resource(MinimapImage(images.add(image)));
+}
+
+// Writes a chunk's tiles into the image, visited chunks that are no longer loaded are drawn darker
+fn paint_chunk(image: &mut Image, coord: &Coord, chunk: &Chunk, loaded: bool) {
+    for (row, tile_row) in chunk.tiles.iter().enumerate() {
+        for (column, tile) in tile_row.iter().enumerate() {
+            let x = coord.x * CHUNK_SIZE as i32 + column as i32;
+            let y = coord.y * CHUNK_SIZE as i32 + row as i32;
+            paint_tile(image, IVec2::new(x, y), &tile.tile_type, loaded);
+        }
+    }
+}
+
+fn paint_tile(image: &mut Image, tile: IVec2, tile_type: &TileType, loaded: bool) {
+    if tile.x < 0 || tile.y < 0 || tile.x >= WORLD_TILES as i32 || tile.y >= WORLD_TILES as i32 {
+        return;
+    }
+    // Image rows go top to bottom, world rows go bottom to top
+    let pixel = (WORLD_TILES - 1 - tile.y as usize) * WORLD_TILES + tile.x as usize;
+
+    let mut color = tile_color(tile_type);
+    if !loaded {
+        for channel in color.iter_mut().take(3) {
+            *channel /= 2;
+        }
+    }
+    image.data[pixel * 4..pixel * 4 + 4].copy_from_slice(&color);
+}
+
+// Only chunks that were loaded or unloaded, and tiles that were edited, since the last frame are repainted
+pub fn paint_chunks(
+    mut loaded: EventReader<ChunkLoaded>,
+    mut unloaded: EventReader<ChunkUnloaded>,
+    mut changed: EventReader<TileChanged>,
+    rendered: Res<RenderedChunks>,
+    derendered: Res<DerenderedChunks>,
+    minimap: Res<MinimapImage>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if loaded.is_empty() && unloaded.is_empty() && changed.is_empty() {
+        return;
+    }
+    let Some(image) = images.get_mut(&minimap.0) else {
+        return;
+    };
+    for ChunkUnloaded(coord) in unloaded.read() {
+        if let Some(chunk) = derendered.chunks.get(coord) {
+            paint_chunk(image, coord, chunk, false);
+        }
+    }
+    for ChunkLoaded(coord) in loaded.read() {
+        if let Some(chunk) = rendered.chunks.get(coord) {
+            paint_chunk(image, coord, chunk, true);
+        }
+    }
+    for event in changed.read() {
+        let loaded = rendered.chunks.contains_key(&event.chunk);
+        paint_tile(image, event.tile, &event.to, loaded);
+    }
+}
+
+pub fn toggle_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<MinimapSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        settings.mode = match settings.mode {
+            MinimapMode::NorthUp => MinimapMode::Rotate,
+            MinimapMode::Rotate => MinimapMode::NorthUp,
+        };
+    }
+}
+
+// Slides the image so the player's tile sits under the marker
+pub fn follow_player(
+    player_query: Query<&Transform, With<Player>>,
+    mut view_query: Query<&mut Style, With<MinimapView>>,
+    mut pivot_query: Query<&mut Transform, (With<MinimapPivot>, Without<Player>)>,
+    settings: Res<MinimapSettings>,
+    mut last_position: Local<Vec2>,
+    mut heading: Local<f32>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let position = player_transform.translation.truncate();
+    if position != *last_position {
+        *heading = (position - *last_position).to_angle();
+        *last_position = position;
+    }
+
+    // Position in image pixels, with fractions so the map scrolls smoothly
+    let x = (position.x + TILE_WIDTH / 2.0) / TILE_WIDTH;
+    let y = WORLD_TILES as f32 - (position.y + TILE_HEIGHT / 2.0) / TILE_HEIGHT;
+    for mut style in view_query.iter_mut() {
+        style.left = Val::Px(-x * settings.scale);
+        style.top = Val::Px(-y * settings.scale);
+    }
+
+    // UI space has y pointing down, so this turns the heading to face the top of the screen
+    let rotation = match settings.mode {
+        MinimapMode::NorthUp => Quat::IDENTITY,
+        MinimapMode::Rotate => Quat::from_rotation_z(*heading - FRAC_PI_2),
+    };
+    for mut transform in pivot_query.iter_mut() {
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+pub fn spawn_minimap(
+    mut commands: Commands,
+    minimap: Res<MinimapImage>,
+    settings: Res<MinimapSettings>,
+) {
+    let half = settings.size / 2.0;
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(16.0),
+                    top: Val::Px(16.0),
+                    width: Val::Px(settings.size),
+                    height: Val::Px(settings.size),
+                    overflow: Overflow::clip(),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..Default::default()
+            },
+            Minimap,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(half),
+                            top: Val::Px(half),
+                            width: Val::Px(0.0),
+                            height: Val::Px(0.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    MinimapPivot,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        ImageBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(WORLD_TILES as f32 * settings.scale),
+                                height: Val::Px(WORLD_TILES as f32 * settings.scale),
+                                ..Default::default()
+                            },
+                            image: UiImage::new(minimap.0.clone()),
+                            ..Default::default()
+                        },
+                        MinimapView,
+                    ));
+                });
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(half - 3.0),
+                        top: Val::Px(half - 3.0),
+                        width: Val::Px(6.0),
+                        height: Val::Px(6.0),
+                        ..Default::default()
+                    },
+                    background_color: Color::srgb(1.0, 0.2, 0.2).into(),
+                    ..Default::default()
+                },
+                PlayerMarker,
+            ));
+        });
+}
+
+pub fn despawn_minimap(mut commands: Commands, query: Query<Entity, With<Minimap>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,38 +1,102 @@
 // Components for the player entity & weapon entity
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::health::components::{Experience, Health};
+use crate::inventory::Inventory;
 
 #[derive(Component)]
 pub struct Player;
 
 #[derive(Component, Clone, Copy)]
 pub struct PlayerStats {
-    pub health: f32,
     pub speed: f32,
 }
 
 // Make a weapon asset
-#[derive(Asset, Reflect)]
+#[derive(Asset, Reflect, Component, Clone)]
 pub struct Weapon {
     pub damage: f32,
     pub range: f32,
+    // Shots per second
     pub fire_rate: f32,
+    // Ranged weapons fire projectiles, melee weapons leave this as None
+    pub projectile: Option<ProjectileSpec>,
 }
 
+impl Weapon {
+    pub fn bow() -> Self {
+        Self {
+            damage: 10.0,
+            range: 900.0,
+            fire_rate: 3.0,
+            projectile: Some(ProjectileSpec {
+                speed: 900.0,
+                lifetime: 1.5,
+                pierce: 0,
+                spread: 0.05,
+                homing: None,
+                radius: 8.0,
+            }),
+        }
+    }
+}
+
+// Describes the projectiles a ranged weapon spawns
+#[derive(Reflect, Clone, Copy, Debug)]
+pub struct ProjectileSpec {
+    // Pixels per second
+    pub speed: f32,
+    // Seconds before the projectile despawns
+    pub lifetime: f32,
+    // Number of enemies the projectile passes through before it is destroyed
+    pub pierce: u32,
+    // Total cone (radians) a shot can stray from the aim direction
+    pub spread: f32,
+    // Turn rate (radians per second) towards the nearest enemy, None flies straight
+    pub homing: Option<f32>,
+    pub radius: f32,
+}
+
+// Time until the weapon can fire again
+#[derive(Component, Default)]
+pub struct FireCooldown(pub f32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tool {
+    Axe,
+    Pickaxe,
+    Hoe,
+    WateringCan,
+    FishingRod,
+}
+
+// The tool in the player's hand, None is bare handed
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct EquippedTool(pub Option<Tool>);
+
 #[derive(Resource)]
 pub struct DespawnedPlayer {
     pub stats: PlayerStats,
+    pub health: Health,
+    pub experience: Experience,
     pub transform: Transform,
+    pub weapon: Weapon,
+    pub tool: EquippedTool,
+    pub inventory: Inventory,
 }
 
 impl DespawnedPlayer {
     pub fn new() -> Self {
         Self {
-            stats: PlayerStats {
-                health: 100.0,
-                speed: 5.0,
-            },
+            stats: PlayerStats { speed: 5.0 },
+            health: Health::new(100.0, 1.0),
+            experience: Experience::default(),
             transform: Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+            weapon: Weapon::bow(),
+            tool: EquippedTool(Some(Tool::Axe)),
+            inventory: Inventory::starting(),
         }
     }
     fn set_stats(&mut self, stats: PlayerStats) {
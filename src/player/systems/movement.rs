@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
+use crate::animation::components::Velocity;
 use crate::camera::Camera;
 use crate::player::components::*;
 use crate::states::AppState::*;
+use crate::weather::Weather;
+use crate::world_map::MapScreen;
 
 use crate::procedural_generation::map::*;
 
@@ -11,7 +14,12 @@ pub struct PlayerMovementPlugin;
 
 impl Plugin for PlayerMovementPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, movement_system.run_if(in_state(InGame)));
+        app.add_systems(
+            Update,
+            movement_system
+                .run_if(in_state(InGame))
+                .run_if(in_state(MapScreen::Closed)),
+        );
     }
 }
 
@@ -19,19 +27,21 @@ fn setup(mut _commands: Commands) {
     // Add setup logic here
 }
 
-fn movement_system(
+pub fn movement_system(
     mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
-    mut player_query: Query<(&mut Transform, &PlayerStats, &mut Sprite), With<Player>>,
+    mut player_query: Query<(&mut Transform, &PlayerStats, &mut Velocity), With<Player>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     window_query: Query<&Window, With<PrimaryWindow>>,
     mut movement: ResMut<last_player_position>,
+    time: Res<Time>,
+    weather: Res<Weather>,
 ) {
     let window = window_query.get_single().unwrap();
     #[allow(unused_assignments)]
     let mut player_translation = Vec3::ZERO;
 
-    if let Ok((mut player_transform, player_stats, mut sprite)) = player_query.get_single_mut() {
-        let player_speed = player_stats.speed;
+    if let Ok((mut player_transform, player_stats, mut velocity)) = player_query.get_single_mut() {
+        let player_speed = player_stats.speed * weather.movement_multiplier();
         player_translation = player_transform.translation;
 
         if keyboard_input.pressed(KeyCode::KeyW) {
@@ -42,13 +52,9 @@ fn movement_system(
         }
         if keyboard_input.pressed(KeyCode::KeyA) {
             player_translation.x -= player_speed;
-            // Turn the player to the left
-            sprite.flip_x = true;
         }
         if keyboard_input.pressed(KeyCode::KeyD) {
             player_translation.x += player_speed;
-            // Turn the player to the right
-            sprite.flip_x = false;
         }
         // normalise player speed
 
@@ -75,6 +81,11 @@ fn movement_system(
             }
         }
 
+        // The animation picks walk or idle from this
+        if time.delta_seconds() > 0.0 {
+            velocity.0 = (player_translation - player_transform.translation).truncate()
+                / time.delta_seconds();
+        }
         player_transform.translation = player_translation;
         *movement = last_player_position {
             x: player_translation.x,
@@ -3,66 +3,117 @@ use bevy::render::camera;
 use bevy::utils::tracing::field::debug;
 use bevy::window::PrimaryWindow;
 
+use crate::animation::components::Velocity;
+use crate::animation::{AnimationSheets, SpriteAnimation};
 use crate::camera::Camera;
+use crate::depth::{Layer, YSort};
+use crate::health::components::{Experience, Health};
+use crate::inventory::Inventory;
 
 use crate::player::components::*;
 use crate::DespawnedYet;
 
+// assets/animations/player.anim.json, drawn from the TX Player sheet
+const PLAYER_SHEET: &str = "player";
+const PLAYER_SCALE: f32 = 2.0;
+// The hero's feet are 26px below the middle of its frame
+const PLAYER_FOOT: f32 = 26.0 * PLAYER_SCALE;
+
 pub fn spawn_player(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    sheets: Res<AnimationSheets>,
+    player_resource: Res<DespawnedPlayer>,
     window_query: Query<&Window, With<PrimaryWindow>>,
 ) {
     let window = window_query.get_single().unwrap();
     let window_width = window.width();
     let window_height = window.height();
 
-    let mut transform =
-        Transform::from_translation(Vec3::new(window_width / 2.0, window_height / 2.0, 0.0))
-            .with_scale(Vec3::splat(5.0));
-    transform.scale = Vec3::splat(5.0);
+    let mut transform = Transform::from_translation(Vec3::new(
+        window_width / 2.0,
+        window_height / 2.0,
+        Layer::Actors.z(),
+    ))
+    .with_scale(Vec3::splat(PLAYER_SCALE));
+    transform.scale = Vec3::splat(PLAYER_SCALE);
+    let (texture, atlas) = sheets.sprite(PLAYER_SHEET).unwrap_or_default();
     commands.spawn((
         SpriteBundle {
-            texture: asset_server.load("sprites/Hero.png"),
+            texture,
             transform,
             ..Default::default()
         },
-        PlayerStats {
-            health: 100.0,
-            speed: 10.0,
-        },
+        atlas,
+        SpriteAnimation::new(PLAYER_SHEET),
+        Velocity::default(),
+        YSort::new(Layer::Actors, PLAYER_FOOT),
+        PlayerStats { speed: 10.0 },
+        Health::new(100.0, 1.0),
+        Experience::default(),
+        Weapon::bow(),
+        FireCooldown::default(),
+        EquippedTool(Some(Tool::Axe)),
+        player_resource.inventory.clone(),
         Player,
     ));
 }
 
+// Everything about the player that survives a trip to the main menu
+type SavedPlayer<'a> = (
+    Entity,
+    &'a Transform,
+    &'a PlayerStats,
+    &'a Health,
+    &'a Experience,
+    &'a Weapon,
+    &'a EquippedTool,
+    &'a Inventory,
+);
+
 pub fn despawn_player(
     mut commands: Commands,
-    query: Query<(Entity, &Transform, &PlayerStats), With<Player>>,
+    query: Query<SavedPlayer, With<Player>>,
     mut player_resource: ResMut<DespawnedPlayer>,
     // mut despawned: ResMut<NextState<DespawnedYet>>,
 ) {
-    for (entity, transform, stats) in query.iter() {
+    for (entity, transform, stats, health, experience, weapon, tool, inventory) in query.iter() {
         // Save the player's health and transformm>()
         player_resource.stats = stats.clone();
+        player_resource.health = *health;
+        player_resource.experience = *experience;
         player_resource.transform = *transform;
+        player_resource.weapon = weapon.clone();
+        player_resource.tool = *tool;
+        player_resource.inventory = inventory.clone();
         commands.entity(entity).despawn();
     }
 }
 
 pub fn respawn_player(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    sheets: Res<AnimationSheets>,
     player_resource: Res<DespawnedPlayer>,
 ) {
     let transform = player_resource.transform;
     let stats = player_resource.stats;
+    let (texture, atlas) = sheets.sprite(PLAYER_SHEET).unwrap_or_default();
     commands.spawn((
         SpriteBundle {
-            texture: asset_server.load("sprites/Hero.png"),
+            texture,
             transform,
             ..Default::default()
         },
+        atlas,
+        SpriteAnimation::new(PLAYER_SHEET),
+        Velocity::default(),
+        YSort::new(Layer::Actors, PLAYER_FOOT),
         stats,
+        player_resource.health,
+        player_resource.experience,
+        player_resource.weapon.clone(),
+        FireCooldown::default(),
+        player_resource.tool,
+        player_resource.inventory.clone(),
         Player,
     ));
 }
@@ -1,5 +1,14 @@
 use bevy::prelude::*;
-use rand::{random, thread_rng, Rng};
+
+use crate::building::Building;
+use crate::drops::StoredDrop;
+use crate::dungeon::Entrance;
+use crate::farming::Crop;
+use crate::procedural_generation::map::Coord;
+use crate::procedural_generation::ores::Ore;
+use crate::procedural_generation::props::Prop;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -16,11 +25,43 @@ pub enum BiomeType {
     Derendered,
 }
 
-// This defines a the Chunk struct, which is used to define a chunk of the world. if has 40x40 tiles, and a biome type.
+// Every biome a chunk can be generated as
+pub const BIOMES: [BiomeType; 6] = [
+    BiomeType::Desert,
+    BiomeType::Forest,
+    BiomeType::Mountain,
+    BiomeType::Ocean,
+    BiomeType::Plains,
+    BiomeType::Tundra,
+];
+
+// Chunks are CHUNK_SIZE x CHUNK_SIZE tiles, tiles[row][column] with row 0 at the bottom
+// Chunks were always laid out 7 tiles apart (CHUNK_WIDTH), so 40 tile chunks drew over their neighbours
+pub const CHUNK_SIZE: usize = 7;
+
+// This defines a the Chunk struct, which is used to define a chunk of the world. if has CHUNK_SIZE x CHUNK_SIZE tiles, and a biome type.
 #[derive(Component, Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Chunk {
     pub tiles: Vec<Vec<Tile>>,
     pub biome: BiomeType,
+    // Filled in by the props pass once the chunk has a place in the world
+    #[serde(default)]
+    pub props: Vec<Prop>,
+    // Filled in by the ores pass, each ore sits in a stone tile
+    #[serde(default)]
+    pub ores: Vec<Ore>,
+    // Placed by the player
+    #[serde(default)]
+    pub buildings: Vec<Building>,
+    // Items left lying around while the chunk is unloaded
+    #[serde(default)]
+    pub drops: Vec<StoredDrop>,
+    // Planted on tilled dirt
+    #[serde(default)]
+    pub crops: Vec<Crop>,
+    // Ways down into dungeons, filled in by the entrances pass
+    #[serde(default)]
+    pub entrances: Vec<Entrance>,
 }
 
 // This defines a the Tile struct, which is used to define a tile in the world. It has a type, and a height.
@@ -37,19 +78,136 @@ pub enum TileType {
     Snow,
     Stone,
     Water,
+    // Only made by tilling grass with a hoe
+    Dirt,
+    // Left behind when stone is mined
+    Gravel,
+}
+
+impl TileType {
+    pub const ALL: [TileType; 7] = [
+        TileType::Grass,
+        TileType::Sand,
+        TileType::Snow,
+        TileType::Stone,
+        TileType::Water,
+        TileType::Dirt,
+        TileType::Gravel,
+    ];
+
+    // Tiles that stop projectiles and other things moving through the world
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, TileType::Stone)
+    }
+
+    pub fn definition(&self) -> TileDefinition {
+        match self {
+            TileType::Grass => TileDefinition {
+                frames: &["tiles/grass.png"],
+                frame_time: 0.0,
+            },
+            TileType::Sand => TileDefinition {
+                frames: &["tiles/sand.png"],
+                frame_time: 0.0,
+            },
+            TileType::Snow => TileDefinition {
+                frames: &["tiles/snow.png"],
+                frame_time: 0.0,
+            },
+            TileType::Stone => TileDefinition {
+                frames: &["tiles/stone.png"],
+                frame_time: 0.0,
+            },
+            TileType::Dirt => TileDefinition {
+                frames: &["tiles/dirt.png"],
+                frame_time: 0.0,
+            },
+            TileType::Gravel => TileDefinition {
+                frames: &["tiles/gravel.png"],
+                frame_time: 0.0,
+            },
+            TileType::Water => TileDefinition {
+                frames: &[
+                    "tiles/water.png",
+                    "tiles/water_1.png",
+                    "tiles/water_2.png",
+                    "tiles/water_3.png",
+                ],
+                frame_time: 0.25,
+            },
+        }
+    }
+}
+
+// How a tile type is drawn, tiles with more than one frame animate off the shared animation clock
+pub struct TileDefinition {
+    pub frames: &'static [&'static str],
+    // Seconds each frame is shown for
+    pub frame_time: f32,
 }
 
 #[derive(Component, Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Position(pub i32, pub i32);
 
-// Ruleset for adjacency
-fn get_adjacency_rules() -> HashMap<TileType, Vec<TileType>> {
+// Ruleset for adjacency, the tiles each tile can sit next to
+// Matches the biomes' own mixes (sand & grass both lie beside stone), & river banks are picked from it
+pub fn get_adjacency_rules() -> HashMap<TileType, Vec<TileType>> {
     let mut rules = HashMap::new();
-    rules.insert(TileType::Grass, vec![TileType::Grass, TileType::Sand]);
-    rules.insert(TileType::Sand, vec![TileType::Sand, TileType::Grass]);
-    rules.insert(TileType::Snow, vec![TileType::Snow, TileType::Stone]);
-    rules.insert(TileType::Stone, vec![TileType::Stone, TileType::Snow]);
-    rules.insert(TileType::Water, vec![TileType::Water, TileType::Sand]); // Water can only be adjacent to sand - there will be rules about where sand can be placed
+    rules.insert(
+        TileType::Grass,
+        vec![
+            TileType::Grass,
+            TileType::Sand,
+            TileType::Stone,
+            TileType::Gravel,
+            TileType::Dirt,
+        ],
+    );
+    rules.insert(
+        TileType::Sand,
+        vec![
+            TileType::Sand,
+            TileType::Grass,
+            TileType::Stone,
+            TileType::Water,
+            TileType::Gravel,
+            TileType::Dirt,
+        ],
+    );
+    rules.insert(
+        TileType::Snow,
+        vec![TileType::Snow, TileType::Stone, TileType::Gravel],
+    );
+    rules.insert(
+        TileType::Stone,
+        vec![
+            TileType::Stone,
+            TileType::Snow,
+            TileType::Grass,
+            TileType::Sand,
+            TileType::Gravel,
+        ],
+    );
+    // Water is lined with sand, or gravel where sand can't go
+    rules.insert(
+        TileType::Water,
+        vec![TileType::Water, TileType::Sand, TileType::Gravel],
+    );
+    rules.insert(
+        TileType::Gravel,
+        vec![
+            TileType::Gravel,
+            TileType::Stone,
+            TileType::Snow,
+            TileType::Sand,
+            TileType::Grass,
+            TileType::Water,
+        ],
+    );
+    rules.insert(
+        TileType::Dirt,
+        vec![TileType::Dirt, TileType::Grass, TileType::Sand],
+    );
     rules
 }
 
@@ -80,9 +238,8 @@ fn get_biome_rules() -> HashMap<BiomeType, Vec<BiomeType>> {
     rules
 }
 
-fn generate_chunk(biome: BiomeType) -> Chunk {
-    let mut rng = rand::thread_rng();
-    let chunk_size = 40;
+fn generate_chunk(biome: BiomeType, rng: &mut impl Rng) -> Chunk {
+    let chunk_size = CHUNK_SIZE;
     let mut tiles = vec![
         vec![
             Tile {
@@ -97,21 +254,21 @@ fn generate_chunk(biome: BiomeType) -> Chunk {
         for tile in row.iter_mut() {
             tile.tile_type = match biome {
                 BiomeType::Desert => {
-                    if random::<f32>() < 0.7 {
+                    if rng.gen::<f32>() < 0.7 {
                         TileType::Sand
                     } else {
                         TileType::Stone
                     }
                 }
                 BiomeType::Forest => {
-                    if random::<f32>() < 0.7 {
+                    if rng.gen::<f32>() < 0.7 {
                         TileType::Grass
                     } else {
                         TileType::Stone
                     }
                 }
                 BiomeType::Mountain => {
-                    if random::<f32>() < 0.7 {
+                    if rng.gen::<f32>() < 0.7 {
                         TileType::Stone
                     } else {
                         TileType::Snow
@@ -138,13 +295,22 @@ fn generate_chunk(biome: BiomeType) -> Chunk {
         }
     }
 
-    Chunk { tiles, biome }
+    Chunk {
+        tiles,
+        biome,
+        props: Vec::new(),
+        ores: Vec::new(),
+        buildings: Vec::new(),
+        drops: Vec::new(),
+        crops: Vec::new(),
+        entrances: Vec::new(),
+    }
 }
 
 impl std::fmt::Display for Chunk {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let mut s = String::new();
-        // Display every tile in the chunk with a a color based on the tile type in a 40x40 grid
+        // Display every tile in the chunk with a a color based on the tile type in a CHUNK_SIZE x CHUNK_SIZE grid
         for row in self.tiles.iter() {
             for tile in row.iter() {
                 let c = match tile.tile_type {
@@ -153,6 +319,8 @@ impl std::fmt::Display for Chunk {
                     TileType::Snow => "❄️",
                     TileType::Stone => "🪨",
                     TileType::Water => "🌊",
+                    TileType::Dirt => "🟫",
+                    TileType::Gravel => "🔘",
                 };
                 s.push_str(c);
             }
@@ -163,45 +331,18 @@ impl std::fmt::Display for Chunk {
 }
 
 pub fn test() {
-    let chunk = generate_chunk(BiomeType::Plains);
+    let chunk = generate_chunk(BiomeType::Plains, &mut thread_rng());
     println!("{}", chunk);
 }
 
-// Function to generate 10x10 chunks of a biome using the adjacency rules and generate_chunk function
-pub fn generate_map() -> Vec<Vec<Chunk>> {
-    let mut rng = thread_rng();
-    // Generate a 10x10 grid of chunks
-    let mut chunks = vec![
-        vec![
-            Chunk {
-                tiles: vec![
-                    vec![
-                        Tile {
-                            tile_type: TileType::Sand
-                        };
-                        10 // The number of tiles in a row
-                    ];
-                    10 // The number of rows
-                ],
-                biome: BiomeType::Plains,
-            };
-            10 // The number of chunks in a row
-        ];
-        10 // The number of rows
-    ];
-    // Generate every chunk in the grid randomly
-    for row in chunks.iter_mut() {
-        for chunk in row.iter_mut() {
-            chunk.biome = match rng.gen_range(0..6) {
-                0 => BiomeType::Desert,
-                1 => BiomeType::Forest,
-                2 => BiomeType::Mountain,
-                3 => BiomeType::Ocean,
-                4 => BiomeType::Plains,
-                _ => BiomeType::Tundra,
-            };
-            *chunk = generate_chunk(chunk.biome);
-        }
-    }
-    chunks
+// The biome of a chunk only depends on the seed & where the chunk is,
+// so later passes can look up any chunk's biome without generating it
+pub fn chunk_biome(seed: &WorldSeed, coord: &Coord) -> BiomeType {
+    BIOMES[(seed.hash(coord.x, coord.y, Pass::Biomes) % BIOMES.len() as u64) as usize]
+}
+
+// The ground tiles of a chunk before any of the later passes
+pub fn generate_base_chunk(seed: &WorldSeed, coord: &Coord) -> Chunk {
+    let biome = chunk_biome(seed, coord);
+    generate_chunk(biome, &mut seed.chunk_rng(coord, Pass::Tiles))
 }
@@ -0,0 +1,133 @@
+// === Chunk sprites ===
+// Sprites for the things kept in a chunk, like crops, ores & buildings
+// They're spawned when the chunk loads, rebuilt when the things change & despawned when it unloads
+
+use bevy::ecs::system::{EntityCommands, StaticSystemParam, SystemParam, SystemParamItem};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::procedural_generation::chunk::Chunk;
+use crate::procedural_generation::map::*;
+
+// Implemented by the event sent when a chunk's things change, one per kind of thing
+pub trait ChunkSprites: Event {
+    // Anything else spawning needs
+    type Param: SystemParam + 'static;
+
+    // The chunk whose sprites are rebuilt
+    fn chunk(&self) -> &Coord;
+
+    fn spawn(
+        sprites: &mut SpriteSpawner,
+        coord: &Coord,
+        chunk: &Chunk,
+        param: &mut SystemParamItem<Self::Param>,
+    );
+}
+
+// Which chunk a sprite belongs to
+#[derive(Component, Debug)]
+pub struct ChunkSprite(pub Coord);
+
+// The sprites of each chunk of one kind, so a chunk's go without looking through the rest
+#[derive(Resource)]
+pub struct SpawnedSprites<E> {
+    pub chunks: HashMap<Coord, Vec<Entity>>,
+    kind: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for SpawnedSprites<E> {
+    fn default() -> Self {
+        Self {
+            chunks: HashMap::new(),
+            kind: PhantomData,
+        }
+    }
+}
+
+// Spawns one chunk's sprites, marking each with the chunk & keeping track of it
+pub struct SpriteSpawner<'a, 'w, 's> {
+    commands: &'a mut Commands<'w, 's>,
+    coord: Coord,
+    spawned: Vec<Entity>,
+}
+
+impl<'a, 'w, 's> SpriteSpawner<'a, 'w, 's> {
+    pub fn spawn(&mut self, bundle: impl Bundle) -> EntityCommands<'_> {
+        let entity = self
+            .commands
+            .spawn((bundle, ChunkSprite(self.coord.clone())))
+            .id();
+        self.spawned.push(entity);
+        self.commands.entity(entity)
+    }
+}
+
+pub struct ChunkSpritesPlugin<E>(PhantomData<fn() -> E>);
+
+impl<E> Default for ChunkSpritesPlugin<E> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E: ChunkSprites> Plugin for ChunkSpritesPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<E>();
+        app.init_resource::<SpawnedSprites<E>>();
+        app.add_systems(
+            Update,
+            (spawn_chunk_sprites::<E>, despawn_chunk_sprites::<E>).chain(),
+        );
+    }
+}
+
+// Spawns the sprites of newly loaded chunks, & respawns a chunk's sprites when E is sent for it
+pub fn spawn_chunk_sprites<E: ChunkSprites>(
+    mut commands: Commands,
+    mut loaded: EventReader<ChunkLoaded>,
+    mut changed: EventReader<E>,
+    rendered: Res<RenderedChunks>,
+    mut spawned: ResMut<SpawnedSprites<E>>,
+    mut param: StaticSystemParam<E::Param>,
+) {
+    let coords: Vec<Coord> = loaded
+        .read()
+        .map(|ChunkLoaded(coord)| coord.clone())
+        .chain(changed.read().map(|event| event.chunk().clone()))
+        .collect();
+
+    for coord in coords {
+        despawn(&mut commands, spawned.chunks.remove(&coord));
+        let Some(chunk) = rendered.chunks.get(&coord) else {
+            continue;
+        };
+        let mut sprites = SpriteSpawner {
+            commands: &mut commands,
+            coord: coord.clone(),
+            spawned: Vec::new(),
+        };
+        E::spawn(&mut sprites, &coord, chunk, &mut param);
+        spawned.chunks.insert(coord, sprites.spawned);
+    }
+}
+
+pub fn despawn_chunk_sprites<E: ChunkSprites>(
+    mut commands: Commands,
+    mut unloaded: EventReader<ChunkUnloaded>,
+    mut spawned: ResMut<SpawnedSprites<E>>,
+) {
+    for ChunkUnloaded(coord) in unloaded.read() {
+        despawn(&mut commands, spawned.chunks.remove(coord));
+    }
+}
+
+// Not a system
+fn despawn(commands: &mut Commands, sprites: Option<Vec<Entity>>) {
+    for entity in sprites.into_iter().flatten() {
+        if let Some(mut sprite) = commands.get_entity(entity) {
+            sprite.despawn();
+        }
+    }
+}
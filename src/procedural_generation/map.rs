@@ -1,183 +1,334 @@
+use crate::animation::AnimationClock;
+use crate::depth::Layer;
+use crate::dungeon::generation::scatter_entrances;
 use crate::player::components::*;
-use crate::procedural_generation::chunk::generate_map;
 use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::ores::scatter_ores;
+use crate::procedural_generation::props::scatter_props;
+use crate::procedural_generation::rivers::carve_rivers;
+use crate::procedural_generation::seed::{load_seed, WorldSeed};
+use crate::procedural_generation::structures::stamp_structures;
+use crate::procedural_generation::tile_map::{DirtyChunks, TileChanged};
+use crate::saves::{SaveData, SaveSet};
 use crate::states::AppState::InGame;
 use crate::Active;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub const TILE_WIDTH: f32 = 60.0;
 pub const TILE_HEIGHT: f32 = 60.0;
-pub const CHUNK_AREA: f32 = 10.0 * 10.0 * TILE_WIDTH * TILE_HEIGHT;
-pub const CHUNK_WIDTH: f32 = 7.0 * TILE_WIDTH;
-pub const CHUNK_HEIGHT: f32 = 7.0 * TILE_HEIGHT;
+pub const CHUNK_WIDTH: f32 = CHUNK_SIZE as f32 * TILE_WIDTH;
+pub const CHUNK_HEIGHT: f32 = CHUNK_SIZE as f32 * TILE_HEIGHT;
 pub const CHUNKS: i32 = 10;
+// Width & height of the generated world in tiles
+pub const WORLD_TILES: usize = CHUNKS as usize * CHUNK_SIZE;
+// Bumped whenever generation changes, a world saved by another version is generated again
+const WORLD_VERSION: u32 = 5;
 
 pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, render_map)
-            .insert_resource(DerenderedChunks {
-                chunks: HashMap::new(),
-                ids: Vec::new(),
-            })
-            .insert_resource(RenderedChunks {
-                chunks: HashMap::new(),
-                ids: Vec::new(),
-            })
-            .init_state::<ChunkLoading>()
-            .insert_resource(NextState::<RenderedYet>::default())
-            .add_systems(Startup, derender_chunks.after(render_map))
-            .add_systems(
-                OnEnter(ChunkLoading::Loading),
-                chunk_loader.run_if(in_state(InGame)),
-            )
-            .add_systems(
-                OnEnter(ChunkLoading::Loading),
-                render_loaded.after(derender_chunks),
-            )
-            .add_systems(
-                OnEnter(ChunkLoading::Loading),
-                derender_unloaded.after(render_loaded),
-            )
-            .insert_resource(ChunkTimer::default())
-            .add_systems(Update, chunk_loader_timer)
-            .init_resource::<last_player_position>();
+        app.add_systems(
+            Startup,
+            ((load_seed, generate_world).chain(), load_tile_textures),
+        )
+        .insert_resource(DerenderedChunks {
+            chunks: HashMap::new(),
+        })
+        .insert_resource(RenderedChunks {
+            chunks: HashMap::new(),
+        })
+        .init_state::<ChunkLoading>()
+        .insert_resource(NextState::<RenderedYet>::default())
+        .add_event::<ChunkLoaded>()
+        .add_event::<ChunkUnloaded>()
+        .add_event::<TileChanged>()
+        .init_resource::<DirtyChunks>()
+        .add_systems(
+            OnEnter(ChunkLoading::Loading),
+            chunk_loader.run_if(in_state(InGame)),
+        )
+        .add_systems(
+            OnEnter(ChunkLoading::Loading),
+            render_loaded.after(chunk_loader),
+        )
+        .add_systems(
+            OnEnter(ChunkLoading::Loading),
+            derender_unloaded.after(render_loaded),
+        )
+        .insert_resource(ChunkTimer::default())
+        .add_systems(Update, chunk_loader_timer)
+        .add_systems(Update, animate_tiles)
+        .add_systems(PostUpdate, refresh_dirty_chunks)
+        .add_systems(Last, save_world.in_set(SaveSet::Collect))
+        .init_resource::<last_player_position>();
     }
 }
 
-#[derive(Resource)]
-struct Map {
-    tiles: Vec<Vec<Chunk>>,
+// Generates every chunk up front, they are only given sprites once the chunk loader picks them up
+// A world saved by this version is restored instead, an older one is generated again keeping what the player made
+fn generate_world(
+    mut derendered_chunks: ResMut<DerenderedChunks>,
+    mut state: ResMut<NextState<RenderedYet>>,
+    save: Res<SaveData>,
+    seed: Res<WorldSeed>,
+) {
+    state.set(RenderedYet::Yes);
+    let saved = save.get::<Vec<(Coord, Chunk)>>("world");
+    if save.get::<u32>("world_version") == Some(WORLD_VERSION) {
+        if let Some(chunks) = saved {
+            derendered_chunks.chunks.extend(chunks);
+            return;
+        }
+    }
+
+    let mut old: HashMap<Coord, Chunk> = saved.unwrap_or_default().into_iter().collect();
+    for y in 0..CHUNKS {
+        for x in 0..CHUNKS {
+            let coord = Coord { x, y };
+            let mut chunk = generate_chunk_at(&seed, &coord);
+            if let Some(old) = old.remove(&coord) {
+                carry_over(&mut chunk, old);
+            }
+            derendered_chunks.chunks.insert(coord, chunk);
+        }
+    }
+}
+
+// Not a system
+// Moves the buildings, drops, crops & looted chests of an older save's chunk into its regenerated chunk
+fn carry_over(chunk: &mut Chunk, old: Chunk) {
+    for crop in old.crops.iter() {
+        if let Some(tile) = chunk
+            .tiles
+            .get_mut(crop.row)
+            .and_then(|tiles| tiles.get_mut(crop.column))
+        {
+            tile.tile_type = TileType::Dirt;
+        }
+    }
+    chunk.buildings = old.buildings;
+    chunk.drops = old.drops;
+    chunk.crops = old.crops;
+    for entrance in chunk.entrances.iter_mut() {
+        entrance.looted = old.entrances.iter().any(|other| {
+            other.looted && other.column == entrance.column && other.row == entrance.row
+        });
+    }
+}
+
+// Runs every seeded pass for one chunk, so any chunk can be rebuilt from the seed alone
+pub fn generate_chunk_at(seed: &WorldSeed, coord: &Coord) -> Chunk {
+    let mut chunk = generate_base_chunk(seed, coord);
+    carve_rivers(&mut chunk, coord, seed);
+    scatter_props(&mut chunk, coord, seed);
+    scatter_ores(&mut chunk, coord, seed);
+    scatter_entrances(&mut chunk, coord, seed);
+    stamp_structures(&mut chunk, coord, seed);
+    chunk
 }
 
-fn render_map(
+fn save_world(
+    rendered: Res<RenderedChunks>,
+    derendered: Res<DerenderedChunks>,
+    seed: Res<WorldSeed>,
+    mut save: ResMut<SaveData>,
+) {
+    save.set("seed", &*seed);
+    save.set("world_version", &WORLD_VERSION);
+    let chunks: Vec<(&Coord, &Chunk)> = rendered
+        .chunks
+        .iter()
+        .chain(derendered.chunks.iter())
+        .collect();
+    save.set("world", &chunks);
+}
+
+struct TileTexture {
+    // The handle every sprite of this tile type is drawn with
+    display: Handle<Image>,
+    frames: Vec<Handle<Image>>,
+    frame_time: f32,
+    // Frame last copied into `display`
+    shown: Option<usize>,
+}
+
+#[derive(Resource)]
+struct TileTextures(HashMap<TileType, TileTexture>);
+
+fn load_tile_textures(
     mut commands: Commands,
-    window: Query<&Window, With<PrimaryWindow>>,
     assets: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
 ) {
-    let map = Map {
-        tiles: generate_map(),
-    };
+    let mut textures = HashMap::new();
+    for tile_type in TileType::ALL {
+        let definition = tile_type.definition();
+        let frames: Vec<Handle<Image>> = definition
+            .frames
+            .iter()
+            .map(|path| assets.load(*path))
+            .collect();
+        // Still tiles draw straight from their image, animated ones share a handle whose pixels get swapped
+        let display = if frames.len() > 1 {
+            images.add(Image::default())
+        } else {
+            frames[0].clone()
+        };
+        textures.insert(
+            tile_type,
+            TileTexture {
+                display,
+                frames,
+                frame_time: definition.frame_time,
+                shown: None,
+            },
+        );
+    }
+    commands.insert_resource(TileTextures(textures));
+}
 
-    let window = window.get_single().unwrap();
-    let window_width = window.width();
-    let window_height = window.height();
-
-    let mut chunk_x_offset = 0.0;
-    let mut chunk_y_offset = 0.0;
-
-    let textures = load_textures(&assets);
-    let mut id: u32 = 0;
-
-    for chunk_row in map.tiles.iter() {
-        for chunk in chunk_row.iter() {
-            let mut tile_x_offset = 0.0;
-            let mut tile_y_offset = 0.0;
-            for tile_row in chunk.tiles.iter() {
-                tile_x_offset = 0.0;
-                for tile in tile_row.iter() {
-                    let texture = textures.get(&tile.tile_type).unwrap();
-
-                    commands.spawn((
-                        SpriteBundle {
-                            texture: texture.clone(),
-                            transform: Transform {
-                                translation: Vec3::new(
-                                    tile_x_offset + chunk_x_offset,
-                                    tile_y_offset + chunk_y_offset,
-                                    -3.0,
-                                ),
-                                ..Default::default()
-                            }
-                            .with_scale(Vec3::splat(1.5)),
-                            ..Default::default()
-                        },
-                        Coord {
-                            x: (tile_x_offset + chunk_x_offset) as i32,
-                            y: (tile_y_offset + chunk_y_offset) as i32,
-                        },
-                        tile.tile_type.clone(),
-                        ID(id),
-                    ));
-
-                    tile_x_offset += TILE_WIDTH;
-                }
-                tile_y_offset += TILE_HEIGHT;
-            }
-            chunk_x_offset += CHUNK_WIDTH;
+// Copies the current frame into the shared image, so every tile of a type animates together
+// without touching the tile entities themselves
+fn animate_tiles(
+    clock: Res<AnimationClock>,
+    mut textures: ResMut<TileTextures>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for texture in textures.0.values_mut() {
+        if texture.frames.len() < 2 {
+            continue;
+        }
+        let frame = clock.frame(texture.frame_time, texture.frames.len());
+        if texture.shown == Some(frame) {
+            continue;
         }
-        chunk_y_offset += CHUNK_HEIGHT;
-        chunk_x_offset = 0.0;
+        // Frames still loading are picked up on a later update
+        let Some(image) = images.get(&texture.frames[frame]).cloned() else {
+            continue;
+        };
+        images.insert(&texture.display, image);
+        texture.shown = Some(frame);
     }
 }
 
-fn load_textures(assets: &Res<AssetServer>) -> HashMap<TileType, Handle<Image>> {
-    let mut textures = HashMap::new();
-    textures.insert(TileType::Grass, assets.load("tiles/grass.png"));
-    textures.insert(TileType::Sand, assets.load("tiles/sand.png"));
-    textures.insert(TileType::Snow, assets.load("tiles/snow.png"));
-    textures.insert(TileType::Stone, assets.load("tiles/stone.png"));
-    textures.insert(TileType::Water, assets.load("tiles/water.png"));
-    textures
+// Brings the sprites of edited loaded chunks back in line with their tiles
+// Unloaded chunks are drawn from their data when they next load, so they're just forgotten
+fn refresh_dirty_chunks(
+    mut dirty: ResMut<DirtyChunks>,
+    rendered: Res<RenderedChunks>,
+    textures: Res<TileTextures>,
+    mut tiles: Query<(&Coord, &mut TileType, &mut Handle<Image>)>,
+) {
+    if dirty.0.is_empty() {
+        return;
+    }
+    for (position, mut tile_type, mut texture) in tiles.iter_mut() {
+        let (coord, column, row) =
+            tile_in_chunk(tile_at(Vec2::new(position.x as f32, position.y as f32)));
+        if !dirty.0.contains(&coord) {
+            continue;
+        }
+        let Some(tile) = rendered
+            .chunks
+            .get(&coord)
+            .map(|chunk| &chunk.tiles[row][column])
+        else {
+            continue;
+        };
+        if *tile_type != tile.tile_type {
+            *tile_type = tile.tile_type.clone();
+            *texture = textures.0[&tile.tile_type].display.clone();
+        }
+    }
+    dirty.0.clear();
 }
 
 #[derive(Resource)]
-struct DerenderedChunks {
-    chunks: HashMap<Coord, Chunk>,
-    ids: Vec<ID>,
+pub struct DerenderedChunks {
+    pub chunks: HashMap<Coord, Chunk>,
 }
 
 #[derive(Resource)]
-struct RenderedChunks {
-    chunks: HashMap<Coord, Chunk>,
-    ids: Vec<ID>,
+pub struct RenderedChunks {
+    pub chunks: HashMap<Coord, Chunk>,
 }
 
-#[derive(Component, Hash, Eq, PartialEq, Debug, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
+#[derive(Component, Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
 }
 
-fn derender_chunks(
-    mut commands: Commands,
-    tiles: Query<(Entity, &Coord, &TileType, &ID)>,
-    mut derendered_chunks: ResMut<DerenderedChunks>,
-    mut state: ResMut<NextState<RenderedYet>>,
-) {
-    let mut chunks: HashMap<Coord, Chunk> = HashMap::new();
-    let mut ids: Vec<ID> = Vec::new();
-    for (entity, coord, tile_type, id) in tiles.iter() {
-        let chunk_coord = Coord {
-            x: coord.x / 400,
-            y: coord.y / 400,
-        };
-        let chunk = chunks.entry(chunk_coord).or_insert(Chunk {
-            tiles: vec![vec![Tile {
-                tile_type: TileType::Grass,
-            }]],
-            biome: BiomeType::Desert,
-        });
-        if chunk.tiles.last().unwrap().len() < 10 {
-            chunk.tiles.last_mut().unwrap().push(Tile {
-                tile_type: tile_type.clone(),
-            });
-        } else {
-            chunk.tiles.push(vec![Tile {
-                tile_type: tile_type.clone(),
-            }]);
-        }
-        commands.entity(entity).despawn();
-        ids.push(id.clone());
+// Tiles are centred on their position, so tile (0, 0) covers -TILE_WIDTH / 2 to TILE_WIDTH / 2
+// Returns the global tile coords of the tile covering a world position
+pub fn tile_at(position: Vec2) -> IVec2 {
+    IVec2::new(
+        ((position.x + TILE_WIDTH / 2.0) / TILE_WIDTH).floor() as i32,
+        ((position.y + TILE_HEIGHT / 2.0) / TILE_HEIGHT).floor() as i32,
+    )
+}
+
+// The coord of the chunk covering a world position
+pub fn chunk_at(position: Vec2) -> Coord {
+    let tile = tile_at(position);
+    Coord {
+        x: tile.x.div_euclid(CHUNK_SIZE as i32),
+        y: tile.y.div_euclid(CHUNK_SIZE as i32),
     }
-    state.set(RenderedYet::Yes);
-    derendered_chunks.chunks = chunks;
-    derendered_chunks.ids = ids;
 }
 
+// World position of a tile inside a chunk
+pub fn tile_position(coord: &Coord, column: usize, row: usize) -> Vec2 {
+    Vec2::new(
+        coord.x as f32 * CHUNK_WIDTH + column as f32 * TILE_WIDTH,
+        coord.y as f32 * CHUNK_HEIGHT + row as f32 * TILE_HEIGHT,
+    )
+}
+
+// World position of the bottom left corner of a chunk
+pub fn chunk_origin(coord: &Coord) -> Vec2 {
+    tile_position(coord, 0, 0) - Vec2::new(TILE_WIDTH / 2.0, TILE_HEIGHT / 2.0)
+}
+
+// Splits global tile coords into the chunk and the column & row inside it
+pub fn tile_in_chunk(tile: IVec2) -> (Coord, usize, usize) {
+    let size = CHUNK_SIZE as i32;
+    let coord = Coord {
+        x: tile.x.div_euclid(size),
+        y: tile.y.div_euclid(size),
+    };
+    (
+        coord,
+        tile.x.rem_euclid(size) as usize,
+        tile.y.rem_euclid(size) as usize,
+    )
+}
+
+// Looks a tile up by its global tile coords, whether or not its chunk is loaded
+pub fn find_tile<'a>(
+    rendered: &'a RenderedChunks,
+    derendered: &'a DerenderedChunks,
+    tile: IVec2,
+) -> Option<&'a Tile> {
+    let (coord, column, row) = tile_in_chunk(tile);
+    let chunk = rendered
+        .chunks
+        .get(&coord)
+        .or_else(|| derendered.chunks.get(&coord))?;
+    chunk.tiles.get(row)?.get(column)
+}
+
+// Sent by the chunk loader when a chunk moves between DerenderedChunks and RenderedChunks
+#[derive(Event, Debug, Clone)]
+pub struct ChunkLoaded(pub Coord);
+
+#[derive(Event, Debug, Clone)]
+pub struct ChunkUnloaded(pub Coord);
+
 #[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 enum RenderedYet {
@@ -190,12 +341,13 @@ fn chunk_loader(
     player_position: Query<&Transform, With<Player>>,
     mut rendered_chunks: ResMut<RenderedChunks>,
     mut derendered_chunks: ResMut<DerenderedChunks>,
+    mut loaded: EventWriter<ChunkLoaded>,
+    mut unloaded: EventWriter<ChunkUnloaded>,
 ) {
     if let Some(player_transform) = player_position.iter().next() {
-        let player_x = player_transform.translation.x;
-        let player_y = player_transform.translation.y;
-        let player_chunk_x = (player_x / 400.0).round() as i32;
-        let player_chunk_y = (player_y / 400.0).round() as i32;
+        let player_chunk = chunk_at(player_transform.translation.truncate());
+        let player_chunk_x = player_chunk.x;
+        let player_chunk_y = player_chunk.y;
 
         // Load chunks within a 2-chunk radius vertically and a 3-chunk radius horizontally
         for y in (player_chunk_y - 2..=player_chunk_y + 2).rev() {
@@ -203,7 +355,8 @@ fn chunk_loader(
                 let coord = Coord { x, y };
                 if !rendered_chunks.chunks.contains_key(&coord) {
                     if let Some(chunk) = derendered_chunks.chunks.remove(&coord) {
-                        rendered_chunks.chunks.insert(coord, chunk);
+                        rendered_chunks.chunks.insert(coord.clone(), chunk);
+                        loaded.send(ChunkLoaded(coord));
                     }
                 }
             }
@@ -220,6 +373,7 @@ fn chunk_loader(
         }
         for coord in to_derender {
             rendered_chunks.chunks.remove(&coord);
+            unloaded.send(ChunkUnloaded(coord));
         }
     }
 }
@@ -227,47 +381,40 @@ fn chunk_loader(
 fn render_loaded(
     mut commands: Commands,
     rendered: Res<RenderedChunks>,
-    assets: Res<AssetServer>,
+    mut loaded: EventReader<ChunkLoaded>,
+    textures: Res<TileTextures>,
     mut chunkloading: ResMut<NextState<ChunkLoading>>,
 ) {
-    if !rendered.is_changed() {
+    if loaded.is_empty() {
         return;
     }
-    let textures = load_textures(&assets);
-    let mut id: u32 = 0;
-    for (coord, chunk) in rendered.chunks.iter() {
-        let mut tile_x_offset = 0.0;
-        let mut tile_y_offset = 0.0;
-        for tile_row in chunk.tiles.iter() {
-            tile_x_offset = 0.0;
-            for tile in tile_row.iter() {
-                let texture = textures.get(&tile.tile_type).unwrap();
+    // Only newly loaded chunks are spawned, chunks that stayed loaded already have their sprites
+    for ChunkLoaded(coord) in loaded.read() {
+        let Some(chunk) = rendered.chunks.get(coord) else {
+            continue;
+        };
+        for (row, tile_row) in chunk.tiles.iter().enumerate() {
+            for (column, tile) in tile_row.iter().enumerate() {
+                let texture = &textures.0[&tile.tile_type].display;
+                let position = tile_position(coord, column, row);
 
                 commands.spawn((
                     SpriteBundle {
                         texture: texture.clone(),
                         transform: Transform {
-                            translation: Vec3::new(
-                                tile_x_offset + coord.x as f32 * 400.0,
-                                tile_y_offset + coord.y as f32 * 400.0,
-                                -3.0,
-                            ),
+                            translation: position.extend(Layer::Ground.z()),
                             ..Default::default()
                         }
                         .with_scale(Vec3::splat(1.5)),
                         ..Default::default()
                     },
                     Coord {
-                        x: (tile_x_offset + coord.x as f32 * 400.0) as i32,
-                        y: (tile_y_offset + coord.y as f32 * 400.0) as i32,
+                        x: position.x as i32,
+                        y: position.y as i32,
                     },
                     tile.tile_type.clone(),
-                    ID(id), // This assumes
                 ));
-
-                tile_x_offset += TILE_WIDTH;
             }
-            tile_y_offset += TILE_HEIGHT;
         }
     }
     chunkloading.set(ChunkLoading::NotLoading);
@@ -280,11 +427,7 @@ fn derender_unloaded(
     mut loading: ResMut<NextState<ChunkLoading>>,
 ) {
     for (entity, coord) in tiles.iter() {
-        // Divide by 400 to get the chunk coord
-        let new = Coord {
-            x: coord.x / 400,
-            y: coord.y / 400,
-        };
+        let new = chunk_at(Vec2::new(coord.x as f32, coord.y as f32));
         if derendered_chunks.chunks.contains_key(&new) {
             commands.entity(entity).despawn();
         }
@@ -323,9 +466,6 @@ fn chunk_loader_timer(
     }
 }
 
-#[derive(Component, Debug, Clone, Eq, PartialEq, Hash)]
-pub struct ID(pub u32);
-
 #[derive(Resource, Default)]
 pub struct last_player_position {
     pub x: f32,
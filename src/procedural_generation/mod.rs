@@ -1,8 +1,15 @@
 use bevy::prelude::*;
 
 pub mod chunk;
+pub mod chunk_sprites;
 pub mod map;
+pub mod ores;
+pub mod props;
+pub mod rivers;
+pub mod seed;
+pub mod structures;
 pub mod systems;
+pub mod tile_map;
 
 pub struct ProceduralGenerationPlugin;
 
@@ -10,5 +17,7 @@ impl Plugin for ProceduralGenerationPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, chunk::test);
         app.add_plugins(map::MapPlugin);
+        app.add_plugins(props::PropsPlugin);
+        app.add_plugins(ores::OresPlugin);
     }
 }
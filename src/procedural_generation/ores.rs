@@ -0,0 +1,210 @@
+// === Ores ===
+// Veins of ore running through the stone of a chunk
+// They are walked out from the world seed per chunk, stored in the chunk & drawn over their stone tiles
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::depth::Layer;
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::chunk_sprites::{
+    ChunkSprites, ChunkSpritesPlugin, SpriteSpawner,
+};
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+
+// Vein sprites are 16px, this makes them a tile wide
+const ORE_SCALE: f32 = TILE_WIDTH / 16.0;
+
+pub struct OresPlugin;
+
+impl Plugin for OresPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ChunkSpritesPlugin::<OresChanged>::default());
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum OreKind {
+    Copper,
+    Iron,
+    Gold,
+}
+
+impl OreKind {
+    // Item dropped when the tile is mined
+    pub fn item(&self) -> &'static str {
+        match self {
+            OreKind::Copper => "copper_ore",
+            OreKind::Iron => "iron_ore",
+            OreKind::Gold => "gold_ore",
+        }
+    }
+
+    // Pickaxe hits it takes to mine
+    pub fn hits(&self) -> u32 {
+        match self {
+            OreKind::Copper => 4,
+            OreKind::Iron => 5,
+            OreKind::Gold => 6,
+        }
+    }
+
+    fn sprite(&self) -> &'static str {
+        match self {
+            OreKind::Copper => "sprites/ores/copper_vein.png",
+            OreKind::Iron => "sprites/ores/iron_vein.png",
+            OreKind::Gold => "sprites/ores/gold_vein.png",
+        }
+    }
+
+    // Steps the random walk takes, rarer ores make shorter veins
+    fn vein_length(&self) -> (usize, usize) {
+        match self {
+            OreKind::Copper => (4, 7),
+            OreKind::Iron => (3, 6),
+            OreKind::Gold => (2, 4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Ore {
+    pub kind: OreKind,
+    // Stone tile within the chunk
+    pub column: usize,
+    pub row: usize,
+}
+
+// Sent when a chunk's ores change, so its sprites are rebuilt
+#[derive(Event, Debug, Clone)]
+pub struct OresChanged(pub Coord);
+
+struct VeinRules {
+    // Least & most veins a chunk gets
+    veins: (u32, u32),
+    kinds: &'static [(OreKind, u32)],
+}
+
+fn vein_rules(biome: BiomeType) -> Option<VeinRules> {
+    match biome {
+        BiomeType::Mountain => Some(VeinRules {
+            veins: (2, 4),
+            kinds: &[
+                (OreKind::Copper, 50),
+                (OreKind::Iron, 35),
+                (OreKind::Gold, 15),
+            ],
+        }),
+        BiomeType::Desert => Some(VeinRules {
+            veins: (1, 2),
+            kinds: &[
+                (OreKind::Copper, 40),
+                (OreKind::Iron, 25),
+                (OreKind::Gold, 35),
+            ],
+        }),
+        BiomeType::Tundra => Some(VeinRules {
+            veins: (0, 1),
+            kinds: &[(OreKind::Iron, 70), (OreKind::Copper, 30)],
+        }),
+        _ => None,
+    }
+}
+
+// Not a system
+fn pick_kind(rng: &mut StdRng, kinds: &[(OreKind, u32)]) -> OreKind {
+    let total: u32 = kinds.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.gen_range(0..total);
+    for (kind, weight) in kinds {
+        if roll < *weight {
+            return *kind;
+        }
+        roll -= weight;
+    }
+    kinds[0].0
+}
+
+// Each vein is a random walk from a random tile, only the stone tiles it crosses get ore
+// The walk only depends on the seed & chunk coord, so a chunk always gets the same veins
+pub fn scatter_ores(chunk: &mut Chunk, coord: &Coord, seed: &WorldSeed) {
+    chunk.ores.clear();
+    let Some(rules) = vein_rules(chunk.biome) else {
+        return;
+    };
+    let mut rng = seed.chunk_rng(coord, Pass::Ores);
+    let last = CHUNK_SIZE - 1;
+
+    for _ in 0..rng.gen_range(rules.veins.0..=rules.veins.1) {
+        let kind = pick_kind(&mut rng, rules.kinds);
+        let (shortest, longest) = kind.vein_length();
+        let mut column = rng.gen_range(0..CHUNK_SIZE);
+        let mut row = rng.gen_range(0..CHUNK_SIZE);
+
+        for _ in 0..rng.gen_range(shortest..=longest) {
+            let stone = chunk.tiles[row][column].tile_type == TileType::Stone;
+            let taken = chunk
+                .ores
+                .iter()
+                .any(|ore| ore.column == column && ore.row == row);
+            if stone && !taken {
+                chunk.ores.push(Ore { kind, column, row });
+            }
+            match rng.gen_range(0..4) {
+                0 => column = (column + 1).min(last),
+                1 => column = column.saturating_sub(1),
+                2 => row = (row + 1).min(last),
+                _ => row = row.saturating_sub(1),
+            }
+        }
+    }
+}
+
+impl ChunkSprites for OresChanged {
+    type Param = Res<'static, AssetServer>;
+
+    fn chunk(&self) -> &Coord {
+        &self.0
+    }
+
+    fn spawn(
+        sprites: &mut SpriteSpawner,
+        coord: &Coord,
+        chunk: &Chunk,
+        asset_server: &mut Res<AssetServer>,
+    ) {
+        for ore in chunk.ores.iter() {
+            let position = tile_position(coord, ore.column, ore.row);
+            sprites.spawn(SpriteBundle {
+                texture: asset_server.load(ore.kind.sprite()),
+                transform: Transform::from_translation(position.extend(Layer::Ground.z() + 0.25))
+                    .with_scale(Vec3::splat(ORE_SCALE)),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_and_coord_give_the_same_ores() {
+        let seed = WorldSeed(1234);
+        let mut veins = 0;
+        for y in 0..CHUNKS {
+            for x in 0..CHUNKS {
+                let coord = Coord { x, y };
+                // Whole chunks, so the base tiles the veins run through are seeded too
+                let first = generate_chunk_at(&seed, &coord);
+                let second = generate_chunk_at(&seed, &coord);
+                assert_eq!(first.ores, second.ores, "chunk {x}, {y}");
+                veins += first.ores.len();
+            }
+        }
+        assert!(veins > 0, "no ore anywhere to compare");
+    }
+}
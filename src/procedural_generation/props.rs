@@ -0,0 +1,548 @@
+// === Props ===
+// Trees, bushes, rocks & flowers scattered over the ground tiles
+// They are generated once per chunk, stored in the chunk & spawned & despawned along with it
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::{SQRT_2, TAU};
+
+use crate::data::{DataFileApp, DataRegistry};
+use crate::day_night::WorldClock;
+use crate::depth::{Layer, YSort};
+use crate::health::components::{LootEntry, LootTable};
+use crate::player::components::Tool;
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+
+const PLANT_SHEET: &str = "Pixel Art Top Down - Basic/Texture/TX Plant.png";
+const PLANT_SHADOW_SHEET: &str = "Pixel Art Top Down - Basic/Texture/TX Shadow Plant.png";
+const PROP_SHEET: &str = "Pixel Art Top Down - Basic/Texture/TX Props.png";
+const PROP_SHADOW_SHEET: &str = "Pixel Art Top Down - Basic/Texture/TX Shadow.png";
+const PROP_SCALE: f32 = 1.5;
+// Candidates tried around each point before it is retired
+const POISSON_ATTEMPTS: usize = 20;
+pub const NODES_PATH: &str = "nodes.json";
+
+pub struct PropsPlugin;
+
+impl Plugin for PropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PropChanged>();
+        app.add_data_file::<NodeRegistry>(NODES_PATH, &["json"]);
+        app.add_systems(Update, (spawn_props, refresh_props, despawn_props));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum PropKind {
+    Tree,
+    Bush,
+    Rock,
+    Flower,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Prop {
+    pub kind: PropKind,
+    // Which of the kind's sprites to draw
+    pub variant: usize,
+    // World units from the bottom left corner of the chunk to the foot of the prop
+    pub x: i32,
+    pub y: i32,
+    // Hits taken since the node was last whole
+    #[serde(default)]
+    pub hits: u32,
+    // WorldClock::seconds when the node was used up, it grows back once its regrow time has passed
+    #[serde(default)]
+    pub depleted_at: Option<u64>,
+}
+
+impl Prop {
+    // A used up node whose kind isn't in the registry stays used up until it is
+    pub fn is_depleted(&self, now: u64, nodes: &NodeRegistry) -> bool {
+        match (self.depleted_at, nodes.get(self.kind)) {
+            (Some(at), Some(node)) => now < at + node.regrow,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+// Sent when a prop changes, so its sprites are rebuilt
+#[derive(Event, Debug, Clone)]
+pub struct PropChanged {
+    pub chunk: Coord,
+    pub index: usize,
+}
+
+// Props that can be chopped, mined or picked for items
+// One entry of assets/nodes.json
+#[derive(Deserialize, Debug, Clone)]
+pub struct NodeDefinition {
+    pub kind: PropKind,
+    // Hits with the right tool before it is used up
+    pub hits: u32,
+    // None can be picked by hand
+    #[serde(default)]
+    pub tool: Option<Tool>,
+    pub yields: LootTable,
+    // Seconds of game time before a used up node grows back
+    pub regrow: u64,
+}
+
+// The whole of assets/nodes.json
+#[derive(Asset, TypePath, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct NodeList(pub Vec<NodeDefinition>);
+
+// Kinds without an entry, like flowers, are only for show
+#[derive(Resource, Debug, Default)]
+pub struct NodeRegistry(pub HashMap<PropKind, NodeDefinition>);
+
+impl NodeRegistry {
+    pub fn get(&self, kind: PropKind) -> Option<&NodeDefinition> {
+        self.0.get(&kind)
+    }
+}
+
+impl DataRegistry for NodeRegistry {
+    type Data = NodeList;
+    type Param = ();
+
+    fn reload(&mut self, _name: &str, list: &NodeList, _param: &mut ()) {
+        self.0 = list
+            .0
+            .iter()
+            .map(|node| (node.kind, node.clone()))
+            .collect();
+    }
+}
+
+// Where each prop's sprite is on its sheet, the shadow sheets use the same layout
+// (min x, min y, max x, max y) in pixels
+const TREES: [[f32; 4]; 3] = [
+    [24.0, 14.0, 138.0, 154.0],
+    [161.0, 17.0, 259.0, 154.0],
+    [295.0, 31.0, 379.0, 152.0],
+];
+const BUSHES: [[f32; 4]; 6] = [
+    [38.0, 198.0, 62.0, 220.0],
+    [98.0, 195.0, 128.0, 223.0],
+    [156.0, 190.0, 198.0, 224.0],
+    [216.0, 185.0, 267.0, 230.0],
+    [282.0, 186.0, 324.0, 234.0],
+    [346.0, 190.0, 390.0, 228.0],
+];
+const ROCKS: [[f32; 4]; 7] = [
+    [3.0, 430.0, 64.0, 475.0],
+    [10.0, 492.0, 23.0, 503.0],
+    [40.0, 490.0, 58.0, 505.0],
+    [68.0, 487.0, 94.0, 507.0],
+    [100.0, 487.0, 126.0, 507.0],
+    [130.0, 484.0, 160.0, 507.0],
+    [162.0, 482.0, 192.0, 510.0],
+];
+// Trunks of the trees above, left behind once they are chopped down
+const STUMPS: [[f32; 4]; 3] = [
+    [73.0, 134.0, 87.0, 154.0],
+    [200.0, 134.0, 215.0, 154.0],
+    [330.0, 134.0, 343.0, 152.0],
+];
+const FLOWERS: [[f32; 4]; 8] = [
+    [8.0, 394.0, 25.0, 403.0],
+    [41.0, 394.0, 57.0, 404.0],
+    [73.0, 394.0, 88.0, 404.0],
+    [102.0, 394.0, 117.0, 405.0],
+    [9.0, 426.0, 21.0, 436.0],
+    [43.0, 427.0, 56.0, 436.0],
+    [9.0, 459.0, 21.0, 467.0],
+    [41.0, 458.0, 53.0, 468.0],
+];
+
+impl PropKind {
+    fn sprites(&self) -> &'static [[f32; 4]] {
+        match self {
+            PropKind::Tree => &TREES,
+            PropKind::Bush => &BUSHES,
+            PropKind::Rock => &ROCKS,
+            PropKind::Flower => &FLOWERS,
+        }
+    }
+
+    // Drawn in place of a used up node, None leaves nothing behind
+    fn depleted_sprite(&self, variant: usize) -> Option<[f32; 4]> {
+        match self {
+            PropKind::Tree => STUMPS.get(variant).copied(),
+            // Rubble
+            PropKind::Rock => Some(ROCKS[1]),
+            PropKind::Bush => Some(BUSHES[0]),
+            PropKind::Flower => None,
+        }
+    }
+
+    fn sheet(&self) -> &'static str {
+        match self {
+            PropKind::Rock => PROP_SHEET,
+            _ => PLANT_SHEET,
+        }
+    }
+
+    fn shadow_sheet(&self) -> Option<&'static str> {
+        match self {
+            PropKind::Tree | PropKind::Bush => Some(PLANT_SHADOW_SHEET),
+            PropKind::Rock => Some(PROP_SHADOW_SHEET),
+            PropKind::Flower => None,
+        }
+    }
+
+    // Flowers lie flat, everything else stands up & is y sorted with the actors
+    fn upright(&self) -> bool {
+        !matches!(self, PropKind::Flower)
+    }
+
+    fn grows_on(&self, tile: &TileType) -> bool {
+        match self {
+            PropKind::Tree => matches!(tile, TileType::Grass | TileType::Snow),
+            PropKind::Bush | PropKind::Flower => matches!(tile, TileType::Grass),
+            PropKind::Rock => !matches!(tile, TileType::Water),
+        }
+    }
+}
+
+// How thickly a biome is covered & what with
+struct Scatter {
+    // Closest two props can be to each other
+    spacing: f32,
+    // Kinds with their relative weights
+    kinds: &'static [(PropKind, u32)],
+}
+
+fn scatter_rules(biome: BiomeType) -> Option<Scatter> {
+    match biome {
+        BiomeType::Forest => Some(Scatter {
+            spacing: 100.0,
+            kinds: &[
+                (PropKind::Tree, 5),
+                (PropKind::Bush, 3),
+                (PropKind::Flower, 2),
+            ],
+        }),
+        BiomeType::Plains => Some(Scatter {
+            spacing: 110.0,
+            kinds: &[
+                (PropKind::Flower, 5),
+                (PropKind::Bush, 3),
+                (PropKind::Tree, 1),
+                (PropKind::Rock, 1),
+            ],
+        }),
+        BiomeType::Desert => Some(Scatter {
+            spacing: 170.0,
+            kinds: &[(PropKind::Rock, 1)],
+        }),
+        BiomeType::Mountain => Some(Scatter {
+            spacing: 120.0,
+            kinds: &[(PropKind::Rock, 1)],
+        }),
+        BiomeType::Tundra => Some(Scatter {
+            spacing: 150.0,
+            kinds: &[(PropKind::Rock, 2), (PropKind::Tree, 1)],
+        }),
+        BiomeType::Ocean | BiomeType::Derendered => None,
+    }
+}
+
+// Second generation pass, fills in a chunk's props from the world seed
+pub fn scatter_props(chunk: &mut Chunk, coord: &Coord, seed: &WorldSeed) {
+    chunk.props.clear();
+    let Some(rules) = scatter_rules(chunk.biome) else {
+        return;
+    };
+    let mut rng = seed.chunk_rng(coord, Pass::Props);
+    let total: u32 = rules.kinds.iter().map(|(_, weight)| weight).sum();
+
+    for point in poisson_disc(&mut rng, CHUNK_WIDTH, rules.spacing) {
+        let mut roll = rng.gen_range(0..total);
+        let Some(kind) = rules.kinds.iter().find_map(|(kind, weight)| {
+            if roll < *weight {
+                Some(*kind)
+            } else {
+                roll -= weight;
+                None
+            }
+        }) else {
+            continue;
+        };
+
+        let column = (point.x / TILE_WIDTH) as usize;
+        let row = (point.y / TILE_HEIGHT) as usize;
+        let Some(tile) = chunk.tiles.get(row).and_then(|tiles| tiles.get(column)) else {
+            continue;
+        };
+        if !kind.grows_on(&tile.tile_type) {
+            continue;
+        }
+
+        chunk.props.push(Prop {
+            kind,
+            variant: rng.gen_range(0..kind.sprites().len()),
+            x: point.x as i32,
+            y: point.y as i32,
+            hits: 0,
+            depleted_at: None,
+        });
+    }
+}
+
+// Whether a prop stands on the tile, props sit anywhere within a chunk so this goes by position
+pub fn prop_on_tile(chunk: &Chunk, coord: &Coord, column: usize, row: usize) -> bool {
+    let centre = tile_position(coord, column, row);
+    let half_tile = Vec2::new(TILE_WIDTH, TILE_HEIGHT) / 2.0;
+    let origin = chunk_origin(coord);
+    chunk.props.iter().any(|prop| {
+        let offset = (origin + Vec2::new(prop.x as f32, prop.y as f32) - centre).abs();
+        offset.x < half_tile.x && offset.y < half_tile.y
+    })
+}
+
+pub fn loot(item: &str, chance: f32, min: u32, max: u32) -> LootEntry {
+    LootEntry {
+        item: item.to_string(),
+        chance,
+        min,
+        max,
+    }
+}
+
+// Bridson's algorithm, evenly spread points in a size x size square no closer than `radius`
+fn poisson_disc(rng: &mut StdRng, size: f32, radius: f32) -> Vec<Vec2> {
+    let cell = radius / SQRT_2;
+    let cells = (size / cell).ceil() as usize;
+    // Each grid cell is small enough to hold at most one point
+    let mut grid: Vec<Option<usize>> = vec![None; cells * cells];
+    let grid_index = |point: Vec2| {
+        let x = ((point.x / cell) as usize).min(cells - 1);
+        let y = ((point.y / cell) as usize).min(cells - 1);
+        y * cells + x
+    };
+
+    let mut points = vec![Vec2::new(
+        rng.gen_range(0.0..size),
+        rng.gen_range(0.0..size),
+    )];
+    grid[grid_index(points[0])] = Some(0);
+    let mut active = vec![0];
+
+    while !active.is_empty() {
+        let slot = rng.gen_range(0..active.len());
+        let origin = points[active[slot]];
+        let mut placed = false;
+
+        for _ in 0..POISSON_ATTEMPTS {
+            let candidate = origin
+                + Vec2::from_angle(rng.gen_range(0.0..TAU)) * rng.gen_range(radius..radius * 2.0);
+            if candidate.x < 0.0 || candidate.y < 0.0 || candidate.x >= size || candidate.y >= size
+            {
+                continue;
+            }
+
+            let cx = (candidate.x / cell) as i32;
+            let cy = (candidate.y / cell) as i32;
+            let too_close = (cy - 2..=cy + 2).any(|y| {
+                (cx - 2..=cx + 2).any(|x| {
+                    if x < 0 || y < 0 || x >= cells as i32 || y >= cells as i32 {
+                        return false;
+                    }
+                    grid[y as usize * cells + x as usize]
+                        .is_some_and(|other| points[other].distance(candidate) < radius)
+                })
+            });
+            if too_close {
+                continue;
+            }
+
+            grid[grid_index(candidate)] = Some(points.len());
+            active.push(points.len());
+            points.push(candidate);
+            placed = true;
+            break;
+        }
+
+        if !placed {
+            active.swap_remove(slot);
+        }
+    }
+    points
+}
+
+// Marks every sprite belonging to a prop, so they go when the chunk unloads
+#[derive(Component, Debug)]
+pub struct PropSprite {
+    pub chunk: Coord,
+    pub index: usize,
+}
+
+fn spawn_props(
+    mut commands: Commands,
+    mut loaded: EventReader<ChunkLoaded>,
+    rendered: Res<RenderedChunks>,
+    assets: Res<AssetServer>,
+    clock: Res<WorldClock>,
+    nodes: Res<NodeRegistry>,
+) {
+    for ChunkLoaded(coord) in loaded.read() {
+        let Some(chunk) = rendered.chunks.get(coord) else {
+            continue;
+        };
+        for (index, prop) in chunk.props.iter().enumerate() {
+            let depleted = prop.is_depleted(clock.seconds(), &nodes);
+            spawn_prop(&mut commands, &assets, coord, index, prop, depleted);
+        }
+    }
+}
+
+// Swaps the sprites of props that were harvested or grew back
+fn refresh_props(
+    mut commands: Commands,
+    mut changed: EventReader<PropChanged>,
+    rendered: Res<RenderedChunks>,
+    sprites: Query<(Entity, &PropSprite)>,
+    assets: Res<AssetServer>,
+    clock: Res<WorldClock>,
+    nodes: Res<NodeRegistry>,
+) {
+    for PropChanged { chunk, index } in changed.read() {
+        for (entity, sprite) in sprites.iter() {
+            if sprite.chunk == *chunk && sprite.index == *index {
+                commands.entity(entity).despawn();
+            }
+        }
+        let Some(prop) = rendered
+            .chunks
+            .get(chunk)
+            .and_then(|loaded| loaded.props.get(*index))
+        else {
+            continue;
+        };
+        let depleted = prop.is_depleted(clock.seconds(), &nodes);
+        spawn_prop(&mut commands, &assets, chunk, *index, prop, depleted);
+    }
+}
+
+fn spawn_prop(
+    commands: &mut Commands,
+    assets: &AssetServer,
+    coord: &Coord,
+    index: usize,
+    prop: &Prop,
+    depleted: bool,
+) {
+    let sprite_rect = if depleted {
+        prop.kind.depleted_sprite(prop.variant)
+    } else {
+        Some(prop.kind.sprites()[prop.variant])
+    };
+    let Some([min_x, min_y, max_x, max_y]) = sprite_rect else {
+        return;
+    };
+    let rect = Rect::new(min_x, min_y, max_x, max_y);
+    let foot = chunk_origin(coord) + Vec2::new(prop.x as f32, prop.y as f32);
+    let layer = if prop.kind.upright() {
+        Layer::Actors
+    } else {
+        Layer::Decoration
+    };
+
+    // Props are anchored at the bottom so the transform sits at their foot
+    let mut sprite = commands.spawn((
+        SpriteBundle {
+            texture: assets.load(prop.kind.sheet()),
+            sprite: Sprite {
+                rect: Some(rect),
+                anchor: Anchor::BottomCenter,
+                ..Default::default()
+            },
+            transform: Transform::from_translation(foot.extend(layer.z()))
+                .with_scale(Vec3::splat(PROP_SCALE)),
+            ..Default::default()
+        },
+        PropSprite {
+            chunk: coord.clone(),
+            index,
+        },
+    ));
+    if prop.kind.upright() {
+        sprite.insert(YSort::new(Layer::Actors, 0.0));
+    }
+
+    // Stumps & rubble are too small to cast a shadow
+    let Some(shadow) = prop.kind.shadow_sheet().filter(|_| !depleted) else {
+        return;
+    };
+    commands.spawn((
+        SpriteBundle {
+            texture: assets.load(shadow),
+            sprite: Sprite {
+                rect: Some(rect),
+                anchor: Anchor::BottomCenter,
+                ..Default::default()
+            },
+            transform: Transform::from_translation(foot.extend(Layer::Decoration.z()))
+                .with_scale(Vec3::splat(PROP_SCALE)),
+            ..Default::default()
+        },
+        PropSprite {
+            chunk: coord.clone(),
+            index,
+        },
+    ));
+}
+
+fn despawn_props(
+    mut commands: Commands,
+    mut unloaded: EventReader<ChunkUnloaded>,
+    sprites: Query<(Entity, &PropSprite)>,
+) {
+    for ChunkUnloaded(coord) in unloaded.read() {
+        for (entity, sprite) in sprites.iter() {
+            if sprite.chunk == *coord {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_harvestable_kind_has_a_node() {
+        let nodes: NodeList =
+            serde_json::from_str(include_str!("../../assets/nodes.json")).unwrap();
+        let kinds: Vec<PropKind> = nodes.0.iter().map(|node| node.kind).collect();
+        for kind in [PropKind::Tree, PropKind::Rock, PropKind::Bush] {
+            assert!(kinds.contains(&kind), "{kind:?} has no node");
+        }
+        assert!(nodes.0.iter().all(|node| node.regrow > 0));
+    }
+
+    #[test]
+    fn unknown_kinds_stay_depleted() {
+        let prop = Prop {
+            kind: PropKind::Tree,
+            variant: 0,
+            x: 0,
+            y: 0,
+            hits: 0,
+            depleted_at: Some(10),
+        };
+        // Before nodes.json has loaded
+        assert!(prop.is_depleted(1_000_000, &NodeRegistry::default()));
+    }
+}
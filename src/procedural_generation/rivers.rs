@@ -0,0 +1,322 @@
+// === Rivers ===
+// Rivers rise in Mountain & Tundra chunks & run downhill until they reach the ocean or pool into a lake
+// Elevation & sources only depend on the seed, so each chunk carves the rivers crossing it
+// without any other chunk having been generated
+
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+use crate::procedural_generation::tile_map::NEIGHBOURS;
+
+// Chance a Mountain or Tundra chunk has a spring
+const SOURCE_CHANCE: f64 = 0.35;
+// Longest a river runs, in tiles
+const MAX_LENGTH: usize = 160;
+// Rivers start a tile wide & widen to three after this many tiles
+const WIDEN_AFTER: usize = 20;
+// Tiles per noise cell, & how much the noise bends the slopes between biomes
+const NOISE_SCALE: f32 = 6.0;
+const NOISE_AMOUNT: f32 = 0.08;
+// Least & most tiles from the middle of a lake to its shore
+const LAKE_RADIUS: (i32, i32) = (1, 3);
+// Lakes a river fills & flows back out of before the last one holds it
+const OVERFLOWS: usize = 2;
+// What a river can be lined with, in order of preference
+const BANKS: [TileType; 2] = [TileType::Sand, TileType::Gravel];
+
+fn biome_height(biome: BiomeType) -> f32 {
+    match biome {
+        BiomeType::Mountain => 1.0,
+        BiomeType::Tundra => 0.85,
+        BiomeType::Forest => 0.55,
+        BiomeType::Plains | BiomeType::Derendered => 0.45,
+        BiomeType::Desert => 0.4,
+        BiomeType::Ocean => 0.0,
+    }
+}
+
+fn smooth(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Not a system
+fn value_noise(seed: &WorldSeed, point: Vec2) -> f32 {
+    let cell = point.floor();
+    let t = point - cell;
+    let corner = |dx: i32, dy: i32| {
+        let hash = seed.hash(cell.x as i32 + dx, cell.y as i32 + dy, Pass::Rivers);
+        (hash >> 40) as f32 / (1u64 << 24) as f32
+    };
+    let bottom = corner(0, 0).lerp(corner(1, 0), smooth(t.x));
+    let top = corner(0, 1).lerp(corner(1, 1), smooth(t.x));
+    bottom.lerp(top, smooth(t.y))
+}
+
+// Blends the heights of the biomes around a tile, so the land slopes from the mountains down to the sea
+pub fn elevation(seed: &WorldSeed, tile: IVec2) -> f32 {
+    // Chunk space, with the middle of each chunk on a whole number
+    let point = (tile.as_vec2() + 0.5) / CHUNK_SIZE as f32 - 0.5;
+    let base = point.floor();
+    let t = point - base;
+    let height = |dx: i32, dy: i32| {
+        let coord = Coord {
+            x: base.x as i32 + dx,
+            y: base.y as i32 + dy,
+        };
+        biome_height(chunk_biome(seed, &coord))
+    };
+    let bottom = height(0, 0).lerp(height(1, 0), smooth(t.x));
+    let top = height(0, 1).lerp(height(1, 1), smooth(t.x));
+    let noise = value_noise(seed, tile.as_vec2() / NOISE_SCALE) - 0.5;
+    bottom.lerp(top, smooth(t.y)) + noise * NOISE_AMOUNT
+}
+
+fn in_world(tile: IVec2) -> bool {
+    tile.x >= 0 && tile.y >= 0 && tile.x < WORLD_TILES as i32 && tile.y < WORLD_TILES as i32
+}
+
+fn chunk_of(tile: IVec2) -> Coord {
+    tile_in_chunk(tile).0
+}
+
+// Where a chunk's spring rises, if it has one
+fn spring(seed: &WorldSeed, coord: &Coord) -> Option<IVec2> {
+    let biome = chunk_biome(seed, coord);
+    if biome != BiomeType::Mountain && biome != BiomeType::Tundra {
+        return None;
+    }
+    let mut rng = seed.chunk_rng(coord, Pass::Rivers);
+    if !rng.gen_bool(SOURCE_CHANCE) {
+        return None;
+    }
+    let size = CHUNK_SIZE as i32;
+    Some(IVec2::new(
+        coord.x * size + rng.gen_range(0..size),
+        coord.y * size + rng.gen_range(0..size),
+    ))
+}
+
+// Follows the steepest way down from a spring, every tile the river & its lakes cover
+// A river that finds nowhere lower pools into a lake, then spills out over the lowest edge it hasn't crossed
+fn trace_river(seed: &WorldSeed, spring: IVec2) -> Vec<IVec2> {
+    let mut water = Vec::new();
+    let mut visited = HashSet::new();
+    let mut overflows = 0;
+    let mut current = spring;
+    for length in 0..MAX_LENGTH {
+        visited.insert(current);
+        let width = if length >= WIDEN_AFTER { 1 } else { 0 };
+        for dy in -width..=width {
+            for dx in -width..=width {
+                water.push(current + IVec2::new(dx, dy));
+            }
+        }
+        if chunk_biome(seed, &chunk_of(current)) == BiomeType::Ocean {
+            break;
+        }
+
+        let here = elevation(seed, current);
+        let lowest = NEIGHBOURS[..4]
+            .iter()
+            .map(|offset| current + *offset)
+            .filter(|tile| in_world(*tile) && !visited.contains(tile))
+            .map(|tile| (tile, elevation(seed, tile)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((next, height)) = lowest else {
+            break;
+        };
+        if height < here {
+            current = next;
+            continue;
+        }
+
+        // Nowhere lower to go, the river pools
+        let mut rng = seed.chunk_rng(&chunk_of(current), Pass::Rivers);
+        let radius = rng.gen_range(LAKE_RADIUS.0..=LAKE_RADIUS.1);
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx * dx + dy * dy <= radius * radius {
+                    water.push(current + IVec2::new(dx, dy));
+                }
+            }
+        }
+        if overflows == OVERFLOWS {
+            break;
+        }
+        overflows += 1;
+        current = next;
+    }
+    water.retain(|tile| in_world(*tile));
+    water
+}
+
+// Every river & lake tile from `min` to `max` inclusive, wherever the rivers rose
+pub fn river_tiles(seed: &WorldSeed, min: IVec2, max: IVec2) -> HashSet<IVec2> {
+    // Furthest a river & the lake at its end can get from its spring
+    let reach = MAX_LENGTH as i32 + LAKE_RADIUS.1;
+    let first = chunk_of((min - reach).max(IVec2::ZERO));
+    let last = chunk_of((max + reach).min(IVec2::splat(WORLD_TILES as i32 - 1)));
+
+    let mut water = HashSet::new();
+    for y in first.y.max(0)..=last.y.min(CHUNKS - 1) {
+        for x in first.x.max(0)..=last.x.min(CHUNKS - 1) {
+            let Some(spring) = spring(seed, &Coord { x, y }) else {
+                continue;
+            };
+            // Too far away to ever get here
+            let gap = (min - spring).max(spring - max).max(IVec2::ZERO);
+            if gap.max_element() > reach {
+                continue;
+            }
+            water.extend(
+                trace_river(seed, spring)
+                    .into_iter()
+                    .filter(|tile| tile.cmpge(min).all() && tile.cmple(max).all()),
+            );
+        }
+    }
+    water
+}
+
+// Not a system
+// The first bank the adjacency rules allow beside all the land around it
+// Gravel goes beside anything the biomes are made of, so it's what's left when sand can't go
+fn bank_tile(rules: &HashMap<TileType, Vec<TileType>>, land: &[TileType]) -> TileType {
+    BANKS
+        .into_iter()
+        .find(|bank| land.iter().all(|tile| rules[bank].contains(tile)))
+        .unwrap_or(TileType::Gravel)
+}
+
+// Turns the river tiles in a chunk to water & lines them with a bank, so water only ever touches water or its bank
+// Banks are worked out from the river tiles & ground just outside the chunk too, so neighbouring chunks agree
+pub fn carve_rivers(chunk: &mut Chunk, coord: &Coord, seed: &WorldSeed) {
+    let size = CHUNK_SIZE as i32;
+    // Two tiles out, so the banks of the tiles around the chunk are known as well
+    let min = IVec2::new(coord.x * size - 2, coord.y * size - 2);
+    let max = min + IVec2::splat(size + 3);
+    let water = river_tiles(seed, min, max);
+    if water.is_empty() {
+        return;
+    }
+
+    let rules = get_adjacency_rules();
+    let base = chunk.clone();
+    let mut neighbours = HashMap::new();
+    // The ground before any rivers are carved, neighbouring chunks are generated for the tiles past the edge
+    let mut ground = |tile: IVec2| {
+        let (at, column, row) = tile_in_chunk(tile);
+        let chunk = if at == *coord {
+            &base
+        } else {
+            neighbours
+                .entry(at.clone())
+                .or_insert_with(|| generate_base_chunk(seed, &at))
+        };
+        chunk.tiles[row][column].tile_type.clone()
+    };
+    let is_bank = |tile: IVec2| {
+        !water.contains(&tile)
+            && NEIGHBOURS
+                .iter()
+                .any(|offset| water.contains(&(tile + *offset)))
+    };
+
+    for row in 0..CHUNK_SIZE {
+        for column in 0..CHUNK_SIZE {
+            let global = IVec2::new(coord.x * size + column as i32, coord.y * size + row as i32);
+            if water.contains(&global) {
+                chunk.tiles[row][column].tile_type = TileType::Water;
+                continue;
+            }
+            if chunk.tiles[row][column].tile_type == TileType::Water || !is_bank(global) {
+                continue;
+            }
+            // Water & other banks can sit beside any bank
+            let land: Vec<TileType> = NEIGHBOURS
+                .iter()
+                .map(|offset| global + *offset)
+                .filter(|tile| in_world(*tile) && !water.contains(tile) && !is_bank(*tile))
+                .map(&mut ground)
+                .filter(|tile| *tile != TileType::Water)
+                .collect();
+            chunk.tiles[row][column].tile_type = bank_tile(&rules, &land);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 4] = [1, 42, 1234, 98765];
+
+    fn whole_world(seed: &WorldSeed) -> HashSet<IVec2> {
+        river_tiles(seed, IVec2::ZERO, IVec2::splat(WORLD_TILES as i32 - 1))
+    }
+
+    #[test]
+    fn chunks_see_the_same_rivers_as_the_whole_world() {
+        let size = CHUNK_SIZE as i32;
+        for seed in SEEDS.map(WorldSeed) {
+            let world = whole_world(&seed);
+            for y in 0..CHUNKS {
+                for x in 0..CHUNKS {
+                    let min = IVec2::new(x, y) * size;
+                    let max = min + IVec2::splat(size - 1);
+                    let expected: HashSet<IVec2> = world
+                        .iter()
+                        .copied()
+                        .filter(|tile| tile.cmpge(min).all() && tile.cmple(max).all())
+                        .collect();
+                    assert_eq!(river_tiles(&seed, min, max), expected, "chunk {x}, {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn carved_banks_follow_the_adjacency_rules() {
+        let rules = get_adjacency_rules();
+        let mut rivers = 0;
+        for seed in SEEDS.map(WorldSeed) {
+            let mut tiles = HashMap::new();
+            for y in 0..CHUNKS {
+                for x in 0..CHUNKS {
+                    let coord = Coord { x, y };
+                    let mut chunk = generate_base_chunk(&seed, &coord);
+                    carve_rivers(&mut chunk, &coord, &seed);
+                    for (row, line) in chunk.tiles.iter().enumerate() {
+                        for (column, tile) in line.iter().enumerate() {
+                            let global = IVec2::new(x, y) * CHUNK_SIZE as i32
+                                + IVec2::new(column as i32, row as i32);
+                            tiles.insert(global, tile.tile_type.clone());
+                        }
+                    }
+                }
+            }
+            let allowed = |tile: IVec2| {
+                NEIGHBOURS
+                    .iter()
+                    .filter_map(|offset| tiles.get(&(tile + *offset)))
+                    .all(|neighbour| rules[&tiles[&tile]].contains(neighbour))
+            };
+
+            let water = whole_world(&seed);
+            rivers += water.len();
+            for tile in water.iter() {
+                assert!(allowed(*tile), "water at {tile} with seed {}", seed.0);
+                for bank in NEIGHBOURS.iter().map(|offset| *tile + *offset) {
+                    if tiles.get(&bank).is_some_and(|t| *t != TileType::Water) {
+                        assert!(allowed(bank), "bank at {bank} with seed {}", seed.0);
+                    }
+                }
+            }
+        }
+        assert!(rivers > 0, "no rivers to check");
+    }
+}
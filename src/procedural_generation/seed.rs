@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{random, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::procedural_generation::map::Coord;
+use crate::saves::SaveData;
+
+// Every seeded generation pass derives its randomness from this, so a world can be rebuilt chunk by chunk
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldSeed(pub u64);
+
+// Separates the passes so props, ores, ... don't all roll the same numbers for a chunk
+#[derive(Debug, Clone, Copy)]
+pub enum Pass {
+    Props = 1,
+    Fishing = 2,
+    Ores = 3,
+    Biomes = 4,
+    Tiles = 5,
+    Rivers = 6,
+    Entrances = 7,
+    Dungeons = 8,
+    Structures = 9,
+}
+
+impl WorldSeed {
+    // An rng for one pass over one chunk, the same seed & chunk always give the same rolls
+    pub fn chunk_rng(&self, coord: &Coord, pass: Pass) -> StdRng {
+        StdRng::seed_from_u64(self.hash(coord.x, coord.y, pass))
+    }
+
+    // A random number for a point, for passes that need one per tile & can't afford an rng each
+    pub fn hash(&self, x: i32, y: i32, pass: Pass) -> u64 {
+        let mut hash = self.0;
+        for value in [x as u64, y as u64, pass as u64] {
+            hash = mix(hash ^ value);
+        }
+        hash
+    }
+
+    // An rng for a pass that isn't tied to a chunk
+    pub fn rng(&self, pass: Pass) -> StdRng {
+        StdRng::seed_from_u64(mix(self.0 ^ pass as u64))
+    }
+}
+
+// splitmix64 finaliser
+fn mix(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+// Reuses the saved seed, a new world gets a random one
+pub fn load_seed(mut commands: Commands, save: Res<SaveData>) {
+    let seed = save
+        .get::<WorldSeed>("seed")
+        .unwrap_or_else(|| WorldSeed(random()));
+    commands.insert_resource(seed);
+}
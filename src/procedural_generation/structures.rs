@@ -0,0 +1,400 @@
+// === Structures ===
+// Ruins, huts, shrines & villages stamped over the ground from small hand drawn templates
+// Where they stand only depends on the seed, the biomes & the rivers, so a structure crossing a chunk border
+// is finished off the same way by its neighbour however much later that is generated
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::props::{Prop, PropKind};
+use crate::procedural_generation::rivers::river_tiles;
+use crate::procedural_generation::seed::{Pass, WorldSeed};
+
+// Fewest tiles between two structures
+const SPACING: i32 = 4;
+// Widest or tallest template, in tiles
+const MAX_SIZE: i32 = 10;
+// Only structures anchored this many chunks away can reach a chunk, or crowd one that does
+const REACH: i32 = ((MAX_SIZE + SPACING) / CHUNK_SIZE as i32) + 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum StructureKind {
+    Ruins,
+    Hut,
+    Shrine,
+    Village,
+}
+
+// Rows from the top, as they're drawn
+// # stone, = gravel, , dirt, : sand, ~ water, ' grass, anything else leaves the ground as it was
+struct Template {
+    rows: &'static [&'static str],
+    // (kind, variant, column, row), rows counted up from the bottom like a chunk's
+    props: &'static [(PropKind, usize, usize, usize)],
+}
+
+#[rustfmt::skip]
+const RUINS: Template = Template {
+    rows: &[
+        "#.#..##",
+        "#.....#",
+        "..===..",
+        "#.=.=..",
+        "#.....#",
+        "##.#.##",
+    ],
+    props: &[
+        (PropKind::Rock, 3, 1, 3),
+        (PropKind::Rock, 0, 5, 4),
+        (PropKind::Bush, 4, 5, 1),
+    ],
+};
+
+#[rustfmt::skip]
+const HUT: Template = Template {
+    rows: &[
+        "#####",
+        "#===#",
+        "#===#",
+        "##=##",
+        "..=..",
+    ],
+    props: &[(PropKind::Flower, 2, 1, 0), (PropKind::Bush, 1, 3, 0)],
+};
+
+#[rustfmt::skip]
+const SHRINE: Template = Template {
+    rows: &[
+        "=.=.=",
+        ".===.",
+        "==#==",
+        ".===.",
+        "=.=.=",
+    ],
+    props: &[(PropKind::Flower, 6, 1, 0), (PropKind::Flower, 3, 3, 4)],
+};
+
+// Two huts & a field round a well
+#[rustfmt::skip]
+const VILLAGE: Template = Template {
+    rows: &[
+        "#####'''''",
+        "#===#'####",
+        "#===#'#==#",
+        "##=##'#==#",
+        "''='''##=#",
+        "''==:::=='",
+        ",,,=:~:'''",
+        ",,,=:::'''",
+        ",,,=''''''",
+    ],
+    props: &[
+        (PropKind::Tree, 0, 8, 8),
+        (PropKind::Flower, 1, 4, 4),
+        (PropKind::Flower, 5, 8, 1),
+        (PropKind::Bush, 2, 9, 0),
+    ],
+};
+
+impl StructureKind {
+    fn template(&self) -> &'static Template {
+        match self {
+            StructureKind::Ruins => &RUINS,
+            StructureKind::Hut => &HUT,
+            StructureKind::Shrine => &SHRINE,
+            StructureKind::Village => &VILLAGE,
+        }
+    }
+
+    // Width & height in tiles
+    fn size(&self) -> IVec2 {
+        let rows = self.template().rows;
+        IVec2::new(rows[0].len() as i32, rows.len() as i32)
+    }
+
+    // Whether it can stand in the biome, a structure has to suit every chunk it covers
+    fn suits(&self, biome: BiomeType) -> bool {
+        match self {
+            StructureKind::Hut | StructureKind::Village => matches!(
+                biome,
+                BiomeType::Plains | BiomeType::Forest | BiomeType::Desert | BiomeType::Tundra
+            ),
+            StructureKind::Ruins | StructureKind::Shrine => {
+                !matches!(biome, BiomeType::Ocean | BiomeType::Derendered)
+            }
+        }
+    }
+}
+
+// How often a biome gets a structure & which
+struct Placement {
+    // Chance a chunk of the biome is picked to hold one
+    chance: f64,
+    // Kinds with their relative weights
+    kinds: &'static [(StructureKind, u32)],
+}
+
+fn placement_rules(biome: BiomeType) -> Option<Placement> {
+    match biome {
+        BiomeType::Plains => Some(Placement {
+            chance: 0.3,
+            kinds: &[
+                (StructureKind::Hut, 3),
+                (StructureKind::Ruins, 2),
+                (StructureKind::Village, 3),
+            ],
+        }),
+        BiomeType::Forest => Some(Placement {
+            chance: 0.25,
+            kinds: &[
+                (StructureKind::Hut, 2),
+                (StructureKind::Ruins, 2),
+                (StructureKind::Shrine, 1),
+                (StructureKind::Village, 2),
+            ],
+        }),
+        BiomeType::Desert => Some(Placement {
+            chance: 0.2,
+            kinds: &[(StructureKind::Ruins, 3), (StructureKind::Shrine, 1)],
+        }),
+        BiomeType::Tundra => Some(Placement {
+            chance: 0.2,
+            kinds: &[(StructureKind::Hut, 1), (StructureKind::Shrine, 2)],
+        }),
+        BiomeType::Mountain => Some(Placement {
+            chance: 0.15,
+            kinds: &[(StructureKind::Shrine, 1)],
+        }),
+        BiomeType::Ocean | BiomeType::Derendered => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Structure {
+    pub kind: StructureKind,
+    // Bottom left tile, counted from the bottom left of the world
+    pub corner: IVec2,
+    // Where two structures would be too close, the higher one stays
+    priority: u64,
+}
+
+impl Structure {
+    // Top right tile
+    fn far_corner(&self) -> IVec2 {
+        self.corner + self.kind.size() - 1
+    }
+
+    fn overlaps(&self, min: IVec2, max: IVec2) -> bool {
+        self.corner.cmple(max).all() && self.far_corner().cmpge(min).all()
+    }
+
+    fn crowds(&self, other: &Structure) -> bool {
+        other.overlaps(self.corner - SPACING, self.far_corner() + SPACING)
+    }
+}
+
+// Not a system
+fn pick_kind(rng: &mut StdRng, kinds: &[(StructureKind, u32)]) -> StructureKind {
+    let total: u32 = kinds.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.gen_range(0..total);
+    for (kind, weight) in kinds {
+        if roll < *weight {
+            return *kind;
+        }
+        roll -= weight;
+    }
+    kinds[0].0
+}
+
+// The structure a chunk would hold, before it's checked against the land & its neighbours
+fn candidate(seed: &WorldSeed, coord: &Coord) -> Option<Structure> {
+    let rules = placement_rules(chunk_biome(seed, coord))?;
+    let mut rng = seed.chunk_rng(coord, Pass::Structures);
+    if !rng.gen_bool(rules.chance) {
+        return None;
+    }
+    let kind = pick_kind(&mut rng, rules.kinds);
+    let size = CHUNK_SIZE as i32;
+    let corner = IVec2::new(
+        coord.x * size + rng.gen_range(0..size),
+        coord.y * size + rng.gen_range(0..size),
+    );
+    Some(Structure {
+        kind,
+        // Pulled back in from the far edges of the world
+        corner: corner.min(IVec2::splat(WORLD_TILES as i32) - kind.size()),
+        priority: rng.gen(),
+    })
+}
+
+// Every structure that stands at least partly in a chunk
+// Candidates covering a biome they don't suit or coming within a tile of a river are dropped,
+// & of any two within SPACING of each other only the higher priority one is kept
+pub fn structures_near(seed: &WorldSeed, coord: &Coord) -> Vec<Structure> {
+    let mut candidates = Vec::new();
+    for y in (coord.y - 2 * REACH).max(0)..=(coord.y + 2 * REACH).min(CHUNKS - 1) {
+        for x in (coord.x - 2 * REACH).max(0)..=(coord.x + 2 * REACH).min(CHUNKS - 1) {
+            candidates.extend(candidate(seed, &Coord { x, y }));
+        }
+    }
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let world = IVec2::splat(WORLD_TILES as i32 - 1);
+    let min = candidates
+        .iter()
+        .fold(world, |min, structure| min.min(structure.corner));
+    let max = candidates.iter().fold(IVec2::ZERO, |max, structure| {
+        max.max(structure.far_corner())
+    });
+    let rivers = river_tiles(seed, min - 1, max + 1);
+
+    let size = CHUNK_SIZE as i32;
+    candidates.retain(|structure| {
+        let (first, last) = (structure.corner / size, structure.far_corner() / size);
+        let suited = (first.y..=last.y)
+            .flat_map(|y| (first.x..=last.x).map(move |x| Coord { x, y }))
+            .all(|coord| structure.kind.suits(chunk_biome(seed, &coord)));
+        // A tile of land all round keeps the river banks whole
+        let dry = !rivers
+            .iter()
+            .any(|tile| structure.overlaps(*tile - 1, *tile + 1));
+        suited && dry
+    });
+
+    let chunk_min = IVec2::new(coord.x, coord.y) * size;
+    let chunk_max = chunk_min + size - 1;
+    candidates
+        .iter()
+        .filter(|structure| structure.overlaps(chunk_min, chunk_max))
+        .filter(|structure| {
+            !candidates
+                .iter()
+                .any(|other| other.priority > structure.priority && other.crowds(structure))
+        })
+        .copied()
+        .collect()
+}
+
+// Last generation pass, stamps the parts of any structures in the chunk over what's been generated
+// Props, ores & entrances under a structure are cleared first so its own props stand alone
+pub fn stamp_structures(chunk: &mut Chunk, coord: &Coord, seed: &WorldSeed) {
+    let size = CHUNK_SIZE as i32;
+    let chunk_corner = IVec2::new(coord.x, coord.y) * size;
+    let tile_size = Vec2::new(TILE_WIDTH, TILE_HEIGHT);
+    let in_chunk =
+        |tile: IVec2| tile.cmpge(IVec2::ZERO).all() && tile.cmplt(IVec2::splat(size)).all();
+    for structure in structures_near(seed, coord) {
+        let template = structure.kind.template();
+        // Counted from the chunk's corner, so they can run off its edges
+        let min = structure.corner - chunk_corner;
+        let max = structure.far_corner() - chunk_corner;
+        let covered = |tile: IVec2| tile.cmpge(min).all() && tile.cmple(max).all();
+        chunk.props.retain(|prop| {
+            let foot = Vec2::new(prop.x as f32, prop.y as f32);
+            !covered((foot / tile_size).floor().as_ivec2())
+        });
+        chunk
+            .ores
+            .retain(|ore| !covered(IVec2::new(ore.column as i32, ore.row as i32)));
+        chunk
+            .entrances
+            .retain(|entrance| !covered(IVec2::new(entrance.column as i32, entrance.row as i32)));
+
+        let height = template.rows.len() as i32;
+        for (index, line) in template.rows.iter().enumerate() {
+            for (column, symbol) in line.chars().enumerate() {
+                let tile = min + IVec2::new(column as i32, height - 1 - index as i32);
+                let tile_type = match symbol {
+                    '#' => TileType::Stone,
+                    '=' => TileType::Gravel,
+                    ',' => TileType::Dirt,
+                    ':' => TileType::Sand,
+                    '~' => TileType::Water,
+                    '\'' => TileType::Grass,
+                    _ => continue,
+                };
+                if in_chunk(tile) {
+                    chunk.tiles[tile.y as usize][tile.x as usize].tile_type = tile_type;
+                }
+            }
+        }
+
+        for (kind, variant, column, row) in template.props.iter() {
+            let tile = min + IVec2::new(*column as i32, *row as i32);
+            if !in_chunk(tile) {
+                continue;
+            }
+            // Stood in the middle of its tile
+            let foot = (tile.as_vec2() + 0.5) * tile_size;
+            chunk.props.push(Prop {
+                kind: *kind,
+                variant: *variant,
+                x: foot.x as i32,
+                y: foot.y as i32,
+                hits: 0,
+                depleted_at: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structures_are_finished_across_chunks() {
+        // Every chunk is generated on its own, the walls & floors of a structure still have to line up
+        let seed = WorldSeed(3);
+        let chunks: Vec<Vec<Chunk>> = (0..CHUNKS)
+            .map(|y| {
+                (0..CHUNKS)
+                    .map(|x| generate_chunk_at(&seed, &Coord { x, y }))
+                    .collect()
+            })
+            .collect();
+        let mut placed = Vec::new();
+        for y in 0..CHUNKS {
+            for x in 0..CHUNKS {
+                for structure in structures_near(&seed, &Coord { x, y }) {
+                    if !placed.contains(&structure) {
+                        placed.push(structure);
+                    }
+                }
+            }
+        }
+        assert!(!placed.is_empty());
+
+        let size = CHUNK_SIZE as i32;
+        let spans_chunks =
+            |structure: &Structure| structure.corner / size != structure.far_corner() / size;
+        assert!(placed.iter().any(spans_chunks));
+        for structure in placed.iter() {
+            assert!(!placed
+                .iter()
+                .any(|other| other != structure && other.crowds(structure)));
+            let template = structure.kind.template();
+            let height = template.rows.len() as i32;
+            for (index, line) in template.rows.iter().enumerate() {
+                for (column, symbol) in line.chars().enumerate() {
+                    let tile =
+                        structure.corner + IVec2::new(column as i32, height - 1 - index as i32);
+                    let (coord, column, row) = tile_in_chunk(tile);
+                    let chunk = &chunks[coord.y as usize][coord.x as usize];
+                    assert!(structure.kind.suits(chunk.biome));
+                    let expected = match symbol {
+                        '#' => TileType::Stone,
+                        '=' => TileType::Gravel,
+                        _ => continue,
+                    };
+                    assert_eq!(chunk.tiles[row][column].tile_type, expected);
+                }
+            }
+        }
+    }
+}
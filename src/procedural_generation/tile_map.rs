@@ -0,0 +1,347 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use crate::procedural_generation::chunk::{Chunk, TileType};
+use crate::procedural_generation::map::*;
+
+// Orthogonal neighbours first, then the diagonals
+pub const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(0, 1),
+    IVec2::new(1, 0),
+    IVec2::new(0, -1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, -1),
+    IVec2::new(-1, 1),
+];
+
+// Sent whenever a tile is changed through the TileMap
+#[derive(Event, Debug, Clone)]
+pub struct TileChanged {
+    // Global tile coords
+    pub tile: IVec2,
+    pub chunk: Coord,
+    pub from: TileType,
+    pub to: TileType,
+}
+
+// Chunks edited since their tile sprites were last refreshed
+#[derive(Resource, Default)]
+pub struct DirtyChunks(pub HashSet<Coord>);
+
+// The first tile a raycast stopped at
+#[derive(Debug, Clone)]
+pub struct RayHit {
+    pub tile: IVec2,
+    pub tile_type: TileType,
+    // Where the ray entered the tile
+    pub position: Vec2,
+    pub distance: f32,
+}
+
+// Reads & edits tiles by world position, across chunk borders and whether or not the chunk is loaded
+#[derive(SystemParam)]
+pub struct TileMap<'w> {
+    rendered: ResMut<'w, RenderedChunks>,
+    derendered: ResMut<'w, DerenderedChunks>,
+    dirty: ResMut<'w, DirtyChunks>,
+    changed: EventWriter<'w, TileChanged>,
+}
+
+// The read only half of TileMap, systems that only look tiles up can run alongside each other
+#[derive(SystemParam)]
+pub struct TileView<'w> {
+    rendered: Res<'w, RenderedChunks>,
+    derendered: Res<'w, DerenderedChunks>,
+}
+
+// Lookups & raycasts shared by TileMap & TileView, borrowed straight from the chunk resources
+#[derive(Clone, Copy)]
+pub struct Tiles<'a> {
+    pub rendered: &'a RenderedChunks,
+    pub derendered: &'a DerenderedChunks,
+}
+
+impl<'w> TileView<'w> {
+    pub fn tiles(&self) -> Tiles<'_> {
+        Tiles {
+            rendered: &self.rendered,
+            derendered: &self.derendered,
+        }
+    }
+}
+
+impl<'w> TileMap<'w> {
+    pub fn tiles(&self) -> Tiles<'_> {
+        Tiles {
+            rendered: &self.rendered,
+            derendered: &self.derendered,
+        }
+    }
+
+    // For the things kept alongside the tiles, tiles themselves must go through set_tile
+    pub fn chunk_mut(&mut self, coord: &Coord) -> Option<&mut Chunk> {
+        match self.rendered.chunks.get_mut(coord) {
+            Some(chunk) => Some(chunk),
+            None => self.derendered.chunks.get_mut(coord),
+        }
+    }
+
+    // Returns what the tile was before, None when there is no chunk there
+    pub fn set_tile(&mut self, position: Vec2, tile_type: TileType) -> Option<TileType> {
+        self.set_tile_at(tile_at(position), tile_type)
+    }
+
+    pub fn set_tile_at(&mut self, tile: IVec2, tile_type: TileType) -> Option<TileType> {
+        let (coord, column, row) = tile_in_chunk(tile);
+        let chunk = match self.rendered.chunks.get_mut(&coord) {
+            Some(chunk) => chunk,
+            None => self.derendered.chunks.get_mut(&coord)?,
+        };
+        let slot = &mut chunk.tiles.get_mut(row)?.get_mut(column)?.tile_type;
+        let from = std::mem::replace(slot, tile_type.clone());
+        if from != tile_type {
+            self.dirty.0.insert(coord.clone());
+            self.changed.send(TileChanged {
+                tile,
+                chunk: coord,
+                from: from.clone(),
+                to: tile_type,
+            });
+        }
+        Some(from)
+    }
+}
+
+impl<'a> Tiles<'a> {
+    pub fn chunk(self, coord: &Coord) -> Option<&'a Chunk> {
+        self.rendered
+            .chunks
+            .get(coord)
+            .or_else(|| self.derendered.chunks.get(coord))
+    }
+
+    // Looks a tile up by its global tile coords
+    pub fn tile(self, tile: IVec2) -> Option<&'a TileType> {
+        find_tile(self.rendered, self.derendered, tile).map(|tile| &tile.tile_type)
+    }
+
+    pub fn get_tile(self, position: Vec2) -> Option<&'a TileType> {
+        self.tile(tile_at(position))
+    }
+
+    // The four tiles sharing an edge with `tile`, missing ones are skipped
+    pub fn neighbours(self, tile: IVec2) -> impl Iterator<Item = (IVec2, &'a TileType)> {
+        self.around(tile, &NEIGHBOURS[..4])
+    }
+
+    // All eight tiles touching `tile`
+    pub fn surrounding(self, tile: IVec2) -> impl Iterator<Item = (IVec2, &'a TileType)> {
+        self.around(tile, &NEIGHBOURS)
+    }
+
+    fn around(
+        self,
+        tile: IVec2,
+        offsets: &'static [IVec2],
+    ) -> impl Iterator<Item = (IVec2, &'a TileType)> {
+        offsets.iter().filter_map(move |offset| {
+            let neighbour = tile + *offset;
+            self.tile(neighbour).map(|tile_type| (neighbour, tile_type))
+        })
+    }
+
+    // Walks every tile the line from `from` to `to` passes through, in order,
+    // and returns the first one `stop` accepts, given the tile's global coords & type
+    pub fn raycast(
+        self,
+        from: Vec2,
+        to: Vec2,
+        stop: impl Fn(IVec2, &TileType) -> bool,
+    ) -> Option<RayHit> {
+        let mut hit = None;
+        let (tile, along) = trace(from, to, |tile| {
+            hit = self.tile(tile).filter(|tile_type| stop(tile, tile_type));
+            hit.is_some()
+        })?;
+        Some(RayHit {
+            tile,
+            tile_type: hit?.clone(),
+            position: from + (to - from) * along,
+            distance: from.distance(to) * along,
+        })
+    }
+}
+
+// Not a system
+// The grid walk behind raycasts, for any grid of TILE_WIDTH x TILE_HEIGHT tiles centred on tile_at
+// Returns the first tile `stop` accepts & how far along the line (0 to 1) it was entered
+pub fn trace(from: Vec2, to: Vec2, mut stop: impl FnMut(IVec2) -> bool) -> Option<(IVec2, f32)> {
+    let size = Vec2::new(TILE_WIDTH, TILE_HEIGHT);
+    // Tile space, where tile (x, y) covers x..x + 1
+    let start = (from + size / 2.0) / size;
+    let delta = (to + size / 2.0) / size - start;
+    let last = tile_at(to);
+    let mut tile = tile_at(from);
+
+    let step = IVec2::new(axis_step(delta.x), axis_step(delta.y));
+    // How far along the ray (0 to 1) one whole tile is on each axis
+    let span = Vec2::new(1.0 / delta.x.abs(), 1.0 / delta.y.abs());
+    // How far along the ray the next tile border is on each axis
+    let mut next = Vec2::new(
+        first_border(start.x, tile.x, step.x, span.x),
+        first_border(start.y, tile.y, step.y, span.y),
+    );
+    let mut along = 0.0;
+
+    loop {
+        if stop(tile) {
+            return Some((tile, along));
+        }
+        if tile == last {
+            return None;
+        }
+        if next.x < next.y {
+            tile.x += step.x;
+            along = next.x;
+            next.x += span.x;
+        } else {
+            tile.y += step.y;
+            along = next.y;
+            next.y += span.y;
+        }
+        // Rounding can step past the end tile, never past the end of the ray
+        if along > 1.0 {
+            return None;
+        }
+    }
+}
+
+// Not a system
+fn axis_step(delta: f32) -> i32 {
+    if delta > 0.0 {
+        1
+    } else if delta < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+// Not a system
+fn first_border(start: f32, tile: i32, step: i32, span: f32) -> f32 {
+    match step {
+        1 => (tile as f32 + 1.0 - start) * span,
+        -1 => (start - tile as f32) * span,
+        _ => f32::INFINITY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procedural_generation::chunk::{BiomeType, Tile, CHUNK_SIZE};
+    use std::collections::HashMap;
+
+    // Grass chunks from -3 to 2 on both axes, so the origin is surrounded, with stone at `stone`
+    fn world(stone: &[IVec2]) -> (RenderedChunks, DerenderedChunks) {
+        let mut chunks = HashMap::new();
+        for y in -3..3 {
+            for x in -3..3 {
+                let tiles = vec![
+                    vec![
+                        Tile {
+                            tile_type: TileType::Grass
+                        };
+                        CHUNK_SIZE
+                    ];
+                    CHUNK_SIZE
+                ];
+                chunks.insert(
+                    Coord { x, y },
+                    Chunk {
+                        tiles,
+                        biome: BiomeType::Plains,
+                        props: Vec::new(),
+                        ores: Vec::new(),
+                        buildings: Vec::new(),
+                        drops: Vec::new(),
+                        crops: Vec::new(),
+                        entrances: Vec::new(),
+                    },
+                );
+            }
+        }
+        for tile in stone {
+            let (coord, column, row) = tile_in_chunk(*tile);
+            chunks.get_mut(&coord).unwrap().tiles[row][column].tile_type = TileType::Stone;
+        }
+        // Split between loaded & unloaded, lookups shouldn't care which
+        let (rendered, derendered) = chunks.into_iter().partition(|(coord, _)| coord.x >= 0);
+        (
+            RenderedChunks { chunks: rendered },
+            DerenderedChunks { chunks: derendered },
+        )
+    }
+
+    fn cast(stone: &[IVec2], from: IVec2, to: IVec2) -> Option<RayHit> {
+        let (rendered, derendered) = world(stone);
+        let tiles = Tiles {
+            rendered: &rendered,
+            derendered: &derendered,
+        };
+        let at = |tile: IVec2| Vec2::new(tile.x as f32 * TILE_WIDTH, tile.y as f32 * TILE_HEIGHT);
+        tiles.raycast(at(from), at(to), |_, tile_type| tile_type.is_blocking())
+    }
+
+    #[test]
+    fn rays_cross_chunk_borders() {
+        let stone = IVec2::new(16, 9);
+        let hit = cast(&[stone], IVec2::new(2, 2), stone).unwrap();
+        assert_eq!(hit.tile, stone);
+        // Stone just off the line is passed by
+        assert!(cast(&[IVec2::new(16, 2)], IVec2::new(2, 2), stone).is_none());
+    }
+
+    #[test]
+    fn rays_work_at_negative_coords() {
+        let hit = cast(
+            &[IVec2::new(-9, -4)],
+            IVec2::new(-1, -4),
+            IVec2::new(-12, -4),
+        )
+        .unwrap();
+        assert_eq!(hit.tile, IVec2::new(-9, -4));
+        assert!(hit
+            .position
+            .abs_diff_eq(Vec2::new(-8.5 * TILE_WIDTH, -4.0 * TILE_HEIGHT), 0.01));
+        assert!((hit.distance - 7.5 * TILE_WIDTH).abs() < 0.01);
+
+        // From loaded chunks into unloaded ones, down & to the left
+        let stone = IVec2::new(-10, -13);
+        let hit = cast(&[stone], IVec2::new(3, 2), stone).unwrap();
+        assert_eq!(hit.tile, stone);
+    }
+
+    #[test]
+    fn axis_aligned_rays() {
+        let (from, to) = (IVec2::new(3, -5), IVec2::new(3, 10));
+        let hit = cast(&[IVec2::new(3, 8)], from, to).unwrap();
+        assert_eq!(hit.tile, IVec2::new(3, 8));
+        assert!((hit.distance - 12.5 * TILE_HEIGHT).abs() < 0.01);
+        assert!(cast(&[IVec2::new(4, 8), IVec2::new(2, 8)], from, to).is_none());
+
+        let hit = cast(&[IVec2::new(-4, 1)], IVec2::new(6, 1), IVec2::new(-8, 1)).unwrap();
+        assert_eq!(hit.tile, IVec2::new(-4, 1));
+        assert!((hit.distance - 9.5 * TILE_WIDTH).abs() < 0.01);
+    }
+
+    #[test]
+    fn rays_starting_in_a_wall_stop_straight_away() {
+        let hit = cast(&[IVec2::new(1, 1)], IVec2::new(1, 1), IVec2::new(5, 5)).unwrap();
+        assert_eq!(hit.tile, IVec2::new(1, 1));
+        assert_eq!(hit.distance, 0.0);
+    }
+}
@@ -0,0 +1,113 @@
+// === Saves ===
+// Everything persistent lives in one JSON file, split into named sections
+// Each feature writes its own section in SaveSet::Collect & reads it back from SaveData on startup
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::states::AppState::*;
+
+// Outside the source tree & gitignored, so playing never touches tracked files
+pub const SAVE_PATH: &str = "saves/last.json";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        // Read straight away so Startup systems can restore their sections
+        app.insert_resource(SaveData::read(SAVE_PATH));
+        app.add_event::<SaveRequested>();
+        app.configure_sets(Last, SaveSet::Prepare.run_if(on_event::<SaveRequested>()));
+        app.configure_sets(
+            Last,
+            SaveSet::Collect
+                .after(SaveSet::Prepare)
+                .run_if(on_event::<SaveRequested>()),
+        );
+        app.configure_sets(
+            Last,
+            SaveSet::Write
+                .after(SaveSet::Collect)
+                .run_if(on_event::<SaveRequested>()),
+        );
+        app.add_systems(OnExit(InGame), request_save);
+        app.add_systems(Last, request_save_on_exit.before(SaveSet::Prepare));
+        app.add_systems(Last, write_save_file.in_set(SaveSet::Write));
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SaveSet {
+    // State kept on entities is copied back into resources first
+    Prepare,
+    // Features copy their state into SaveData
+    Collect,
+    // SaveData is written to disk
+    Write,
+}
+
+#[derive(Event, Default)]
+pub struct SaveRequested;
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct SaveData(pub HashMap<String, serde_json::Value>);
+
+impl SaveData {
+    // A missing or unreadable save starts a fresh game
+    pub fn read(path: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, section: &str) -> Option<T> {
+        let value = self.0.get(section)?;
+        match serde_json::from_value(value.clone()) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                warn!("Ignoring save section {}: {}", section, error);
+                None
+            }
+        }
+    }
+
+    pub fn set<T: Serialize>(&mut self, section: &str, value: &T) {
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                self.0.insert(section.to_string(), value);
+            }
+            Err(error) => warn!("Could not save section {}: {}", section, error),
+        }
+    }
+}
+
+fn request_save(mut requests: EventWriter<SaveRequested>) {
+    requests.send(SaveRequested);
+}
+
+// Closing the window saves too
+fn request_save_on_exit(mut exit: EventReader<AppExit>, mut requests: EventWriter<SaveRequested>) {
+    if exit.read().next().is_some() {
+        requests.send(SaveRequested);
+    }
+}
+
+fn write_save_file(save: Res<SaveData>) {
+    let path = Path::new(SAVE_PATH);
+    let result = serde_json::to_string(&*save)
+        .map_err(|error| error.to_string())
+        .and_then(|json| {
+            path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(path, json))
+                .map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        warn!("Could not write {}: {}", SAVE_PATH, error);
+    }
+}
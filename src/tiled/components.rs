@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+// The top bits of a gid say how the tile is flipped, the rest is the tile
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+// Only used by hexagonal maps, but has to be masked off all the same
+const ROTATED_HEXAGONAL: u32 = 0x1000_0000;
+const GID_MASK: u32 =
+    !(FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL);
+
+// A custom property set on a map, layer, tile or object in Tiled
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    // Colours, files & class properties are kept as Tiled wrote them
+    Other(String),
+}
+
+impl Property {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Property::String(value) | Property::Other(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Property::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+pub type Properties = HashMap<String, Property>;
+
+// A cell of a tile layer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LayerTile {
+    // 0 is an empty cell, otherwise the tile's id plus its tileset's first gid
+    pub gid: u32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub flip_diagonal: bool,
+}
+
+impl LayerTile {
+    pub fn from_raw(raw: u32) -> Self {
+        Self {
+            gid: raw & GID_MASK,
+            flip_x: raw & FLIPPED_HORIZONTALLY != 0,
+            flip_y: raw & FLIPPED_VERTICALLY != 0,
+            flip_diagonal: raw & FLIPPED_DIAGONALLY != 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gid == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TileLayer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    // Row major from the top left, the way Tiled stores them
+    pub tiles: Vec<LayerTile>,
+    pub properties: Properties,
+}
+
+impl TileLayer {
+    // x to the right, y down from the top like Tiled
+    pub fn tile(&self, x: u32, y: u32) -> Option<LayerTile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.tiles.get((y * self.width + x) as usize).copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TiledObject {
+    pub id: u32,
+    pub name: String,
+    // Tiled 1.9 renamed an object's type to its class, either is read into this
+    pub class: String,
+    // Pixels from the top left of the map, tile objects are anchored at their bottom left
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // Set when the object is a tile
+    pub tile: Option<LayerTile>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub objects: Vec<TiledObject>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone)]
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TilesetTile {
+    pub class: String,
+    pub properties: Properties,
+}
+
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct TiledTileset {
+    pub name: String,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub spacing: u32,
+    pub margin: u32,
+    pub tile_count: u32,
+    pub columns: u32,
+    // Path of the sheet relative to the assets folder
+    pub image_path: String,
+    pub image: Handle<Image>,
+    // Only tiles with a class or properties are listed, keyed by id within the tileset
+    pub tiles: HashMap<u32, TilesetTile>,
+}
+
+// A tileset & the gid of its first tile in the map using it
+#[derive(Debug, Clone)]
+pub struct MapTileset {
+    pub first_gid: u32,
+    pub tileset: TiledTileset,
+}
+
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct TiledMap {
+    // In tiles
+    pub width: u32,
+    pub height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub tilesets: Vec<MapTileset>,
+    // Bottom to top, as drawn
+    pub layers: Vec<Layer>,
+    pub properties: Properties,
+}
+
+impl TiledMap {
+    // The tileset a gid belongs to & the tile's id within it
+    pub fn tileset_for(&self, gid: u32) -> Option<(&TiledTileset, u32)> {
+        if gid == 0 {
+            return None;
+        }
+        let entry = self
+            .tilesets
+            .iter()
+            .filter(|entry| entry.first_gid <= gid)
+            .max_by_key(|entry| entry.first_gid)?;
+        Some((&entry.tileset, gid - entry.first_gid))
+    }
+
+    // The class & properties of a placed tile, if its tileset gave it any
+    pub fn tile_info(&self, gid: u32) -> Option<&TilesetTile> {
+        let (tileset, id) = self.tileset_for(gid)?;
+        tileset.tiles.get(&id)
+    }
+}
+
+#[derive(Debug)]
+pub enum TiledError {
+    Io(String),
+    Xml(String),
+    // A required attribute or element is missing or malformed
+    Format(String),
+    // Layer data in an encoding or compression we can't read
+    Unsupported(String),
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TiledError::Io(error) => write!(f, "could not read file: {}", error),
+            TiledError::Xml(error) => write!(f, "invalid xml: {}", error),
+            TiledError::Format(error) => write!(f, "invalid tiled file: {}", error),
+            TiledError::Unsupported(error) => write!(f, "unsupported: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for TiledError {}
@@ -0,0 +1,274 @@
+// === Exporting ===
+// Writes a region of a generated world out as a Tiled map, so it can be touched up by hand & imported again
+// Every tile of the tileset is one TileType in one BiomeType, both kept as properties,
+// which import::map_to_chunks reads back
+
+use bevy::prelude::*;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::seed::WorldSeed;
+
+// tiles/*.png are 40px
+const TILE_PIXELS: u32 = 40;
+// Where `export-tmx` writes to, relative to the working directory, kept out of git
+const EXPORT_DIR: &str = "assets/exports";
+const TILESET_FILE: &str = "world.tsx";
+// From the export folder back to the tile images
+const TILES_FROM_EXPORTS: &str = "../";
+
+pub struct Export {
+    pub map: String,
+    pub tileset: String,
+}
+
+// Enum names are written the same way they're saved
+fn name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+// Id of a tile type & biome's tile in the tileset
+fn tile_id(tile_type: &TileType, biome: BiomeType) -> usize {
+    let type_index = TileType::ALL
+        .iter()
+        .position(|other| other == tile_type)
+        .unwrap_or(0);
+    let biome_index = BIOMES.iter().position(|other| *other == biome).unwrap_or(0);
+    type_index * BIOMES.len() + biome_index
+}
+
+// A collection of images tileset, `images` is the path from the tileset to the assets folder
+pub fn tileset(images: &str) -> String {
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(
+            r#"<tileset version="1.8" tiledversion="1.8.2" name="world" tilewidth="{0}" tileheight="{0}" tilecount="{1}" columns="0">"#,
+            TILE_PIXELS,
+            TileType::ALL.len() * BIOMES.len()
+        ),
+        r#" <grid orientation="orthogonal" width="1" height="1"/>"#.to_string(),
+    ];
+    for tile_type in TileType::ALL.iter() {
+        for biome in BIOMES {
+            lines.extend([
+                format!(r#" <tile id="{}">"#, tile_id(tile_type, biome)),
+                "  <properties>".to_string(),
+                format!(r#"   <property name="biome" value="{}"/>"#, name(&biome)),
+                format!(
+                    r#"   <property name="tile_type" value="{}"/>"#,
+                    name(tile_type)
+                ),
+                "  </properties>".to_string(),
+                // Animated tiles are shown with their first frame
+                format!(
+                    r#"  <image width="{0}" height="{0}" source="{1}{2}"/>"#,
+                    TILE_PIXELS,
+                    images,
+                    tile_type.definition().frames[0]
+                ),
+                " </tile>".to_string(),
+            ]);
+        }
+    }
+    lines.push("</tileset>".to_string());
+    lines.join("\n") + "\n"
+}
+
+// Generates the chunks from `from` to `to` inclusive, the same way the game does, & writes them as a map
+// The map refers to its tileset by `tileset_source`
+pub fn export_region(seed: &WorldSeed, from: &Coord, to: &Coord, tileset_source: &str) -> String {
+    let min = Coord {
+        x: from.x.min(to.x),
+        y: from.y.min(to.y),
+    };
+    let max = Coord {
+        x: from.x.max(to.x),
+        y: from.y.max(to.y),
+    };
+    let width = (max.x - min.x + 1) as usize * CHUNK_SIZE;
+    let height = (max.y - min.y + 1) as usize * CHUNK_SIZE;
+
+    let mut gids = vec![vec![0; width]; height];
+    let mut props = Vec::new();
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let coord = Coord { x, y };
+            let chunk = generate_chunk_at(seed, &coord);
+            let corner = IVec2::new(x - min.x, y - min.y) * CHUNK_SIZE as i32;
+            for (row, tiles) in chunk.tiles.iter().enumerate() {
+                for (column, tile) in tiles.iter().enumerate() {
+                    // Tiled counts rows down from the top
+                    let map_row = height - 1 - (corner.y as usize + row);
+                    gids[map_row][corner.x as usize + column] =
+                        tile_id(&tile.tile_type, chunk.biome) + 1;
+                }
+            }
+            // Props are points at their feet, in Tiled's pixels from the top left
+            let region_corner = chunk_origin(&coord) - chunk_origin(&min);
+            for prop in chunk.props.iter() {
+                let foot = region_corner + Vec2::new(prop.x as f32, prop.y as f32);
+                let pixels = foot * TILE_PIXELS as f32 / Vec2::new(TILE_WIDTH, TILE_HEIGHT);
+                let top = (height as u32 * TILE_PIXELS) as f32 - pixels.y;
+                props.push((prop.clone(), pixels.x, top));
+            }
+        }
+    }
+
+    let rows: Vec<String> = gids
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect();
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(
+            r#"<map version="1.8" tiledversion="1.8.2" orientation="orthogonal" renderorder="right-down" width="{}" height="{}" tilewidth="{2}" tileheight="{2}" infinite="0" nextlayerid="3" nextobjectid="{3}">"#,
+            width,
+            height,
+            TILE_PIXELS,
+            props.len() + 1
+        ),
+        // Where the region came from, so it can be found again
+        " <properties>".to_string(),
+        format!(r#"  <property name="seed" value="{}"/>"#, seed.0),
+        format!(
+            r#"  <property name="from_chunk" value="{},{}"/>"#,
+            min.x, min.y
+        ),
+        format!(
+            r#"  <property name="to_chunk" value="{},{}"/>"#,
+            max.x, max.y
+        ),
+        " </properties>".to_string(),
+        format!(r#" <tileset firstgid="1" source="{}"/>"#, tileset_source),
+        format!(
+            r#" <layer id="1" name="Tiles" width="{}" height="{}">"#,
+            width, height
+        ),
+        r#"  <data encoding="csv">"#.to_string(),
+        rows.join(",\n"),
+        "</data>".to_string(),
+        " </layer>".to_string(),
+        r#" <objectgroup id="2" name="Props">"#.to_string(),
+    ];
+    for (id, (prop, x, y)) in props.iter().enumerate() {
+        lines.extend([
+            format!(
+                r#"  <object id="{}" type="{}" x="{}" y="{}">"#,
+                id + 1,
+                name(&prop.kind),
+                x,
+                y
+            ),
+            format!(
+                r#"   <properties><property name="variant" type="int" value="{}"/></properties>"#,
+                prop.variant
+            ),
+            "   <point/>".to_string(),
+            "  </object>".to_string(),
+        ]);
+    }
+    lines.push(" </objectgroup>".to_string());
+    lines.push("</map>".to_string());
+    lines.join("\n") + "\n"
+}
+
+pub fn export(seed: &WorldSeed, from: &Coord, to: &Coord) -> Export {
+    Export {
+        map: export_region(seed, from, to, TILESET_FILE),
+        tileset: tileset(TILES_FROM_EXPORTS),
+    }
+}
+
+// `export-tmx <seed> <from x> <from y> <to x> <to y>` writes a region to assets/exports instead of starting the game
+// Returns None when the arguments aren't an export
+pub fn run_from_args(args: &[String]) -> Option<Result<String, String>> {
+    if args.first().map(String::as_str) != Some("export-tmx") {
+        return None;
+    }
+    let usage = || "usage: export-tmx <seed> <from x> <from y> <to x> <to y>".to_string();
+    if args.len() != 6 {
+        return Some(Err(usage()));
+    }
+    let Ok(seed) = args[1].parse::<u64>() else {
+        return Some(Err(usage()));
+    };
+    let Ok(bounds) = args[2..]
+        .iter()
+        .map(|value| value.parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return Some(Err(usage()));
+    };
+    let from = Coord {
+        x: bounds[0],
+        y: bounds[1],
+    };
+    let to = Coord {
+        x: bounds[2],
+        y: bounds[3],
+    };
+
+    let export = export(&WorldSeed(seed), &from, &to);
+    let file = format!("world_{}_{}_{}_{}_{}.tmx", seed, from.x, from.y, to.x, to.y);
+    let directory = Path::new(EXPORT_DIR);
+    let result = fs::create_dir_all(directory)
+        .and_then(|_| fs::write(directory.join(TILESET_FILE), &export.tileset))
+        .and_then(|_| fs::write(directory.join(&file), &export.map));
+    Some(
+        result
+            .map(|_| format!("wrote {}/{}", EXPORT_DIR, file))
+            .map_err(|error| format!("could not write {}: {}", EXPORT_DIR, error)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiled::components::MapTileset;
+    use crate::tiled::import::map_to_chunks;
+    use crate::tiled::loader::{parse_map, parse_tileset};
+
+    #[test]
+    fn exported_regions_import_unchanged() {
+        let seed = WorldSeed(7);
+        let (from, to) = (Coord { x: 3, y: 1 }, Coord { x: 1, y: 2 });
+        let (mut map, external) =
+            parse_map(&export_region(&seed, &from, &to, "world.tsx")).unwrap();
+        assert_eq!(external, vec![(1, "world.tsx".to_string())]);
+        map.tilesets.push(MapTileset {
+            first_gid: 1,
+            tileset: parse_tileset(&tileset("../")).unwrap(),
+        });
+        assert_eq!(
+            (map.width, map.height),
+            (3 * CHUNK_SIZE as u32, 2 * CHUNK_SIZE as u32)
+        );
+
+        let chunks = map_to_chunks(&map, &Coord { x: 1, y: 1 }).unwrap();
+        assert_eq!(chunks.len(), 6);
+        for (coord, chunk) in chunks {
+            let generated = generate_chunk_at(&seed, &coord);
+            assert_eq!(chunk.biome, generated.biome);
+            assert_eq!(chunk.tiles, generated.tiles);
+            let props = |chunk: &Chunk| -> Vec<_> {
+                chunk
+                    .props
+                    .iter()
+                    .map(|prop| (prop.kind, prop.variant, prop.x, prop.y))
+                    .collect()
+            };
+            assert_eq!(props(&chunk), props(&generated), "{:?}", coord);
+        }
+    }
+}
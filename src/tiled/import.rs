@@ -0,0 +1,203 @@
+// === Importing ===
+// Turns a Tiled map into chunks, so areas drawn by hand can be dropped into the procedural world
+// A tile's TileType comes from a `tile_type` property on its tileset tile, failing that on its layer,
+// tiles with neither keep the map's `tile_type` (Grass if unset)
+// A chunk's biome is the map's `biome` (Plains if unset), unless a tile in it has a `biome` property
+// Objects whose class names a prop kind (Tree, Bush, Rock, Flower) become props
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+use crate::procedural_generation::chunk::*;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::props::{Prop, PropKind};
+use crate::tiled::components::*;
+
+// Names of enum variants are matched the same way they're saved
+fn parse_name<T: DeserializeOwned>(property: &Property) -> Result<T, TiledError> {
+    let name = property
+        .as_str()
+        .ok_or_else(|| TiledError::Format(format!("expected a name, got {:?}", property)))?;
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| TiledError::Format(format!("unknown name '{}'", name)))
+}
+
+// Chunks covering the map, with its bottom left corner on the bottom left corner of `origin`
+pub fn map_to_chunks(map: &TiledMap, origin: &Coord) -> Result<Vec<(Coord, Chunk)>, TiledError> {
+    let base = match map.properties.get("tile_type") {
+        Some(property) => parse_name(property)?,
+        None => TileType::Grass,
+    };
+    let biome = match map.properties.get("biome") {
+        Some(property) => parse_name(property)?,
+        None => BiomeType::Plains,
+    };
+
+    let size = CHUNK_SIZE as u32;
+    let mut chunks = HashMap::new();
+    for y in 0..map.height.div_ceil(size) {
+        for x in 0..map.width.div_ceil(size) {
+            let coord = Coord {
+                x: origin.x + x as i32,
+                y: origin.y + y as i32,
+            };
+            let tiles = vec![
+                vec![
+                    Tile {
+                        tile_type: base.clone()
+                    };
+                    CHUNK_SIZE
+                ];
+                CHUNK_SIZE
+            ];
+            chunks.insert(
+                coord,
+                Chunk {
+                    tiles,
+                    biome,
+                    props: Vec::new(),
+                    ores: Vec::new(),
+                    buildings: Vec::new(),
+                    drops: Vec::new(),
+                    crops: Vec::new(),
+                    entrances: Vec::new(),
+                },
+            );
+        }
+    }
+
+    let origin_tile = IVec2::new(origin.x, origin.y) * CHUNK_SIZE as i32;
+    for layer in map.layers.iter() {
+        match layer {
+            Layer::Tiles(layer) => {
+                let layer_type = layer.properties.get("tile_type");
+                for y in 0..layer.height.min(map.height) {
+                    for x in 0..layer.width.min(map.width) {
+                        let Some(tile) = layer.tile(x, y).filter(|tile| !tile.is_empty()) else {
+                            continue;
+                        };
+                        // Tiled counts rows down from the top, chunks count up from the bottom
+                        let global =
+                            origin_tile + IVec2::new(x as i32, (map.height - 1 - y) as i32);
+                        let (coord, column, row) = tile_in_chunk(global);
+                        let Some(chunk) = chunks.get_mut(&coord) else {
+                            continue;
+                        };
+                        let properties = map.tile_info(tile.gid).map(|info| &info.properties);
+                        let tile_type = properties
+                            .and_then(|properties| properties.get("tile_type"))
+                            .or(layer_type);
+                        if let Some(property) = tile_type {
+                            chunk.tiles[row][column].tile_type = parse_name(property)?;
+                        }
+                        if let Some(property) =
+                            properties.and_then(|properties| properties.get("biome"))
+                        {
+                            chunk.biome = parse_name(property)?;
+                        }
+                    }
+                }
+            }
+            Layer::Objects(layer) => {
+                for object in layer.objects.iter() {
+                    let Ok(kind) = parse_name::<PropKind>(&Property::String(object.class.clone()))
+                    else {
+                        continue;
+                    };
+                    let variant = object
+                        .properties
+                        .get("variant")
+                        .and_then(Property::as_int)
+                        .unwrap_or(0)
+                        .max(0) as usize;
+                    let position = chunk_origin(origin) + object_foot(map, object);
+                    let coord = chunk_at(position);
+                    let Some(chunk) = chunks.get_mut(&coord) else {
+                        continue;
+                    };
+                    let local = position - chunk_origin(&coord);
+                    chunk.props.push(Prop {
+                        kind,
+                        variant,
+                        x: local.x.round() as i32,
+                        y: local.y.round() as i32,
+                        hits: 0,
+                        depleted_at: None,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut chunks: Vec<(Coord, Chunk)> = chunks.into_iter().collect();
+    chunks.sort_by_key(|(coord, _)| (coord.y, coord.x));
+    Ok(chunks)
+}
+
+// World units from the bottom left of the map to the bottom middle of an object
+// Tile objects hang up from their y, everything else hangs down from it
+fn object_foot(map: &TiledMap, object: &TiledObject) -> Vec2 {
+    let bottom = if object.tile.is_some() {
+        object.y
+    } else {
+        object.y + object.height
+    };
+    let pixels = Vec2::new(
+        object.x + object.width / 2.0,
+        (map.height * map.tile_height) as f32 - bottom,
+    );
+    pixels
+        * Vec2::new(
+            TILE_WIDTH / map.tile_width as f32,
+            TILE_HEIGHT / map.tile_height as f32,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiled::loader::parse_map;
+
+    #[test]
+    fn tiles_and_props_land_in_chunks() {
+        // A 2x8 map, one chunk wide & two high, with stone along its top row & a tree in the bottom corner
+        let xml = r#"<map orientation="orthogonal" width="2" height="8" tilewidth="16" tileheight="16">
+            <properties><property name="biome" value="Mountain"/></properties>
+            <tileset firstgid="1" name="inline" tilewidth="16" tileheight="16" tilecount="2" columns="2">
+              <tile id="1"><properties><property name="tile_type" value="Stone"/></properties></tile>
+            </tileset>
+            <layer name="ground" width="2" height="8">
+              <data encoding="csv">2,2,1,1,1,1,1,1,1,1,1,1,1,1,1,1</data>
+            </layer>
+            <objectgroup name="props">
+              <object id="1" class="Tree" x="0" y="128" width="16" height="16" gid="1"/>
+              <object id="2" class="Signpost" x="0" y="0"/>
+            </objectgroup>
+          </map>"#;
+        let (map, _) = parse_map(xml).unwrap();
+        let chunks = map_to_chunks(&map, &Coord { x: 2, y: 3 }).unwrap();
+
+        let coords: Vec<&Coord> = chunks.iter().map(|(coord, _)| coord).collect();
+        assert_eq!(coords, vec![&Coord { x: 2, y: 3 }, &Coord { x: 2, y: 4 }]);
+        assert!(chunks
+            .iter()
+            .all(|(_, chunk)| chunk.biome == BiomeType::Mountain));
+
+        // Tiled's top row is the eighth row up, the bottom row of the upper chunk
+        // Rows above the map keep the base tile
+        let upper = &chunks[1].1;
+        assert_eq!(upper.tiles[0][0].tile_type, TileType::Stone);
+        assert_eq!(upper.tiles[0][1].tile_type, TileType::Stone);
+        assert_eq!(upper.tiles[1][0].tile_type, TileType::Grass);
+
+        let lower = &chunks[0].1;
+        assert_eq!(lower.props.len(), 1);
+        assert_eq!(lower.props[0].kind, PropKind::Tree);
+        assert_eq!(
+            (lower.props[0].x, lower.props[0].y),
+            ((TILE_WIDTH / 2.0) as i32, 0)
+        );
+        assert!(upper.props.is_empty());
+    }
+}
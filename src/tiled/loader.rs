@@ -0,0 +1,521 @@
+// === Tiled loaders ===
+// Reads .tmx maps & .tsx tilesets made in the Tiled editor
+// Parsing works on plain strings so it can be used outside the asset server too
+
+use base64::Engine;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+use std::io::Read;
+use std::str::FromStr;
+
+use crate::tiled::components::*;
+
+#[derive(Default)]
+pub struct TmxLoader;
+
+impl AssetLoader for TmxLoader {
+    type Asset = TiledMap;
+    type Settings = ();
+    type Error = TiledError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<TiledMap, TiledError> {
+        let xml = read_to_string(reader).await?;
+        let (mut map, external) = parse_map(&xml)?;
+        let map_path = load_context.asset_path().clone();
+
+        for tileset in map.tilesets.iter_mut() {
+            load_image(&mut tileset.tileset, &map_path, load_context)?;
+        }
+        // External tilesets are read as part of the map, so the map is complete once it's loaded
+        for (first_gid, source) in external {
+            let path = map_path
+                .resolve_embed(&source)
+                .map_err(|error| TiledError::Format(error.to_string()))?;
+            let bytes = load_context
+                .read_asset_bytes(path.clone())
+                .await
+                .map_err(|error| TiledError::Io(error.to_string()))?;
+            let xml =
+                String::from_utf8(bytes).map_err(|error| TiledError::Io(error.to_string()))?;
+            let mut tileset = parse_tileset(&xml)?;
+            load_image(&mut tileset, &path, load_context)?;
+            map.tilesets.push(MapTileset { first_gid, tileset });
+        }
+        map.tilesets.sort_by_key(|entry| entry.first_gid);
+        Ok(map)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmx"]
+    }
+}
+
+#[derive(Default)]
+pub struct TsxLoader;
+
+impl AssetLoader for TsxLoader {
+    type Asset = TiledTileset;
+    type Settings = ();
+    type Error = TiledError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<TiledTileset, TiledError> {
+        let xml = read_to_string(reader).await?;
+        let mut tileset = parse_tileset(&xml)?;
+        let path = load_context.asset_path().clone();
+        load_image(&mut tileset, &path, load_context)?;
+        Ok(tileset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tsx"]
+    }
+}
+
+async fn read_to_string(reader: &mut Reader<'_>) -> Result<String, TiledError> {
+    let mut xml = String::new();
+    reader
+        .read_to_string(&mut xml)
+        .await
+        .map_err(|error| TiledError::Io(error.to_string()))?;
+    Ok(xml)
+}
+
+// Tileset images are relative to the file the tileset was written in
+fn load_image(
+    tileset: &mut TiledTileset,
+    relative_to: &bevy::asset::AssetPath<'static>,
+    load_context: &mut LoadContext,
+) -> Result<(), TiledError> {
+    if tileset.image_path.is_empty() {
+        return Ok(());
+    }
+    let path = relative_to
+        .resolve_embed(&tileset.image_path)
+        .map_err(|error| TiledError::Format(error.to_string()))?;
+    tileset.image_path = path.to_string();
+    tileset.image = load_context.load(path);
+    Ok(())
+}
+
+// === Parsing ===
+
+// Returns the map with its embedded tilesets, & the first gid & path of each external one
+pub fn parse_map(xml: &str) -> Result<(TiledMap, Vec<(u32, String)>), TiledError> {
+    let document = Document::parse(xml).map_err(|error| TiledError::Xml(error.to_string()))?;
+    let root = document.root_element();
+    if root.tag_name().name() != "map" {
+        return Err(TiledError::Format("expected a <map>".to_string()));
+    }
+    if root.attribute("orientation").unwrap_or("orthogonal") != "orthogonal" {
+        return Err(TiledError::Unsupported(
+            "only orthogonal maps can be loaded".to_string(),
+        ));
+    }
+    if root.attribute("infinite") == Some("1") {
+        return Err(TiledError::Unsupported(
+            "infinite maps can't be loaded".to_string(),
+        ));
+    }
+
+    let mut map = TiledMap {
+        width: attribute(root, "width")?,
+        height: attribute(root, "height")?,
+        tile_width: attribute(root, "tilewidth")?,
+        tile_height: attribute(root, "tileheight")?,
+        tilesets: Vec::new(),
+        layers: Vec::new(),
+        properties: read_properties(root)?,
+    };
+    let mut external = Vec::new();
+    for child in root.children().filter(Node::is_element) {
+        if child.tag_name().name() != "tileset" {
+            continue;
+        }
+        let first_gid = attribute(child, "firstgid")?;
+        match child.attribute("source") {
+            Some(source) => external.push((first_gid, source.to_string())),
+            None => map.tilesets.push(MapTileset {
+                first_gid,
+                tileset: read_tileset(child)?,
+            }),
+        }
+    }
+    read_layers(root, &mut map.layers)?;
+    Ok((map, external))
+}
+
+pub fn parse_tileset(xml: &str) -> Result<TiledTileset, TiledError> {
+    let document = Document::parse(xml).map_err(|error| TiledError::Xml(error.to_string()))?;
+    let root = document.root_element();
+    if root.tag_name().name() != "tileset" {
+        return Err(TiledError::Format("expected a <tileset>".to_string()));
+    }
+    read_tileset(root)
+}
+
+fn read_tileset(node: Node) -> Result<TiledTileset, TiledError> {
+    let image_path = child(node, "image")
+        .and_then(|image| image.attribute("source"))
+        .unwrap_or_default()
+        .to_string();
+    let mut tiles = HashMap::new();
+    for tile in node.children().filter(|child| child.has_tag_name("tile")) {
+        tiles.insert(
+            attribute(tile, "id")?,
+            TilesetTile {
+                class: class(tile),
+                properties: read_properties(tile)?,
+            },
+        );
+    }
+    Ok(TiledTileset {
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        tile_width: attribute(node, "tilewidth")?,
+        tile_height: attribute(node, "tileheight")?,
+        spacing: optional_attribute(node, "spacing")?.unwrap_or(0),
+        margin: optional_attribute(node, "margin")?.unwrap_or(0),
+        tile_count: attribute(node, "tilecount")?,
+        columns: attribute(node, "columns")?,
+        image_path,
+        image: Handle::default(),
+        tiles,
+    })
+}
+
+// Group layers are flattened into the list, image layers are skipped
+fn read_layers(parent: Node, layers: &mut Vec<Layer>) -> Result<(), TiledError> {
+    for node in parent.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "layer" => {
+                let width = attribute(node, "width")?;
+                let height = attribute(node, "height")?;
+                let data = child(node, "data")
+                    .ok_or_else(|| TiledError::Format("tile layer without <data>".to_string()))?;
+                layers.push(Layer::Tiles(TileLayer {
+                    name: node.attribute("name").unwrap_or_default().to_string(),
+                    width,
+                    height,
+                    tiles: read_data(data, width * height)?,
+                    properties: read_properties(node)?,
+                }));
+            }
+            "objectgroup" => {
+                let mut objects = Vec::new();
+                for object in node.children().filter(|child| child.has_tag_name("object")) {
+                    objects.push(read_object(object)?);
+                }
+                layers.push(Layer::Objects(ObjectLayer {
+                    name: node.attribute("name").unwrap_or_default().to_string(),
+                    objects,
+                    properties: read_properties(node)?,
+                }));
+            }
+            "group" => read_layers(node, layers)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn read_object(node: Node) -> Result<TiledObject, TiledError> {
+    Ok(TiledObject {
+        id: attribute(node, "id")?,
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        class: class(node),
+        x: optional_attribute(node, "x")?.unwrap_or(0.0),
+        y: optional_attribute(node, "y")?.unwrap_or(0.0),
+        width: optional_attribute(node, "width")?.unwrap_or(0.0),
+        height: optional_attribute(node, "height")?.unwrap_or(0.0),
+        tile: optional_attribute(node, "gid")?.map(LayerTile::from_raw),
+        properties: read_properties(node)?,
+    })
+}
+
+// Tile data is plain <tile> elements, csv, or base64 of little endian u32s that may be zlib or gzip compressed
+fn read_data(data: Node, count: u32) -> Result<Vec<LayerTile>, TiledError> {
+    if child(data, "chunk").is_some() {
+        return Err(TiledError::Unsupported(
+            "chunked layer data is only used by infinite maps".to_string(),
+        ));
+    }
+    let text = data.text().unwrap_or_default().trim();
+    let raw: Vec<u32> = match data.attribute("encoding") {
+        None => data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| optional_attribute(tile, "gid").map(Option::unwrap_or_default))
+            .collect::<Result<_, _>>()?,
+        Some("csv") => text
+            .split(',')
+            // Tiled leaves a comma at the end of every row
+            .filter(|gid| !gid.trim().is_empty())
+            .map(|gid| {
+                gid.trim()
+                    .parse()
+                    .map_err(|_| TiledError::Format(format!("bad gid '{}' in csv", gid.trim())))
+            })
+            .collect::<Result<_, _>>()?,
+        Some("base64") => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(text.split_whitespace().collect::<String>())
+                .map_err(|error| TiledError::Format(error.to_string()))?;
+            let bytes = decompress(bytes, data.attribute("compression"))?;
+            if bytes.len() % 4 != 0 {
+                return Err(TiledError::Format(
+                    "base64 data isn't a whole number of gids".to_string(),
+                ));
+            }
+            bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+                .collect()
+        }
+        Some(encoding) => {
+            return Err(TiledError::Unsupported(format!(
+                "'{}' layer encoding",
+                encoding
+            )))
+        }
+    };
+    if raw.len() != count as usize {
+        return Err(TiledError::Format(format!(
+            "layer has {} tiles, expected {}",
+            raw.len(),
+            count
+        )));
+    }
+    Ok(raw.into_iter().map(LayerTile::from_raw).collect())
+}
+
+fn decompress(bytes: Vec<u8>, compression: Option<&str>) -> Result<Vec<u8>, TiledError> {
+    let mut decoded = Vec::new();
+    let result = match compression {
+        None => return Ok(bytes),
+        Some("zlib") => ZlibDecoder::new(bytes.as_slice()).read_to_end(&mut decoded),
+        Some("gzip") => GzDecoder::new(bytes.as_slice()).read_to_end(&mut decoded),
+        Some(other) => return Err(TiledError::Unsupported(format!("'{}' compression", other))),
+    };
+    result.map_err(|error| TiledError::Format(error.to_string()))?;
+    Ok(decoded)
+}
+
+fn read_properties(node: Node) -> Result<Properties, TiledError> {
+    let mut properties = Properties::new();
+    let Some(list) = child(node, "properties") else {
+        return Ok(properties);
+    };
+    for property in list
+        .children()
+        .filter(|child| child.has_tag_name("property"))
+    {
+        let name = property
+            .attribute("name")
+            .ok_or_else(|| TiledError::Format("property without a name".to_string()))?;
+        // Multi-line strings are written as the element's text instead of a value
+        let value = property
+            .attribute("value")
+            .or_else(|| property.text())
+            .unwrap_or_default();
+        let bad = || TiledError::Format(format!("bad value '{}' for property {}", value, name));
+        let value = match property.attribute("type").unwrap_or("string") {
+            "string" => Property::String(value.to_string()),
+            "int" => Property::Int(value.parse().map_err(|_| bad())?),
+            "float" => Property::Float(value.parse().map_err(|_| bad())?),
+            "bool" => Property::Bool(value.parse().map_err(|_| bad())?),
+            _ => Property::Other(value.to_string()),
+        };
+        properties.insert(name.to_string(), value);
+    }
+    Ok(properties)
+}
+
+// Not a system
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+// Tiled 1.9 writes `class` where older versions wrote `type`
+fn class(node: Node) -> String {
+    node.attribute("class")
+        .or_else(|| node.attribute("type"))
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn attribute<T: FromStr>(node: Node, name: &str) -> Result<T, TiledError> {
+    optional_attribute(node, name)?.ok_or_else(|| {
+        TiledError::Format(format!(
+            "<{}> is missing '{}'",
+            node.tag_name().name(),
+            name
+        ))
+    })
+}
+
+fn optional_attribute<T: FromStr>(node: Node, name: &str) -> Result<Option<T>, TiledError> {
+    node.attribute(name)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                TiledError::Format(format!(
+                    "bad '{}' on <{}>: {}",
+                    name,
+                    node.tag_name().name(),
+                    value
+                ))
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use std::io::Write;
+
+    const GIDS: [u32; 4] = [1, 0x8000_0002, 3, 0];
+
+    fn map_with_data(data: &str) -> String {
+        format!(
+            r#"<map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" infinite="0">
+                 <layer name="ground" width="2" height="2">{}</layer>
+               </map>"#,
+            data
+        )
+    }
+
+    fn base64_data(bytes: &[u8], compression: &str) -> String {
+        format!(
+            r#"<data encoding="base64"{}>{}</data>"#,
+            compression,
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        )
+    }
+
+    fn layer_tiles(xml: &str) -> Vec<LayerTile> {
+        let (map, _) = parse_map(xml).unwrap();
+        match &map.layers[0] {
+            Layer::Tiles(layer) => layer.tiles.clone(),
+            Layer::Objects(_) => panic!("expected a tile layer"),
+        }
+    }
+
+    #[test]
+    fn sample_map_parses() {
+        let (map, external) = parse_map(include_str!(
+            "../../assets/kenney_tiny-dungeon/Tiled/sampleMap.tmx"
+        ))
+        .unwrap();
+        assert_eq!((map.width, map.height), (32, 20));
+        assert_eq!(external, vec![(1, "sampleSheet.tsx".to_string())]);
+        assert_eq!(map.layers.len(), 3);
+
+        let Layer::Tiles(dungeon) = &map.layers[0] else {
+            panic!("expected a tile layer");
+        };
+        assert_eq!(dungeon.tiles.len(), 32 * 20);
+        // 1610612787 is tile 51 flipped vertically & diagonally, a quarter turn
+        let turned = dungeon.tile(1, 0).unwrap();
+        assert_eq!(turned.gid, 51);
+        assert!(!turned.flip_x && turned.flip_y && turned.flip_diagonal);
+
+        let tileset = parse_tileset(include_str!(
+            "../../assets/kenney_tiny-dungeon/Tiled/sampleSheet.tsx"
+        ))
+        .unwrap();
+        assert_eq!(
+            (tileset.tile_count, tileset.columns, tileset.spacing),
+            (132, 12, 1)
+        );
+        assert_eq!(tileset.image_path, "../Tilemap/tilemap.png");
+    }
+
+    #[test]
+    fn every_encoding_reads_the_same_tiles() {
+        let bytes: Vec<u8> = GIDS.iter().flat_map(|gid| gid.to_le_bytes()).collect();
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(&bytes).unwrap();
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&bytes).unwrap();
+
+        let expected: Vec<LayerTile> = GIDS.into_iter().map(LayerTile::from_raw).collect();
+        let encodings = [
+            r#"<data><tile gid="1"/><tile gid="2147483650"/><tile gid="3"/><tile/></data>"#
+                .to_string(),
+            "<data encoding=\"csv\">\n1,2147483650,\n3,0\n</data>".to_string(),
+            base64_data(&bytes, ""),
+            base64_data(&zlib.finish().unwrap(), r#" compression="zlib""#),
+            base64_data(&gzip.finish().unwrap(), r#" compression="gzip""#),
+        ];
+        for data in encodings {
+            assert_eq!(layer_tiles(&map_with_data(&data)), expected, "{}", data);
+        }
+        assert!(expected[1].flip_x);
+    }
+
+    #[test]
+    fn bad_data_is_an_error() {
+        let short = map_with_data(r#"<data encoding="csv">1,2,3</data>"#);
+        assert!(matches!(parse_map(&short), Err(TiledError::Format(_))));
+        let zstd = map_with_data(r#"<data encoding="base64" compression="zstd">AAAA</data>"#);
+        assert!(matches!(parse_map(&zstd), Err(TiledError::Unsupported(_))));
+    }
+
+    #[test]
+    fn objects_and_properties_are_read() {
+        let xml = r#"<map orientation="orthogonal" width="1" height="1" tilewidth="16" tileheight="16">
+            <properties>
+              <property name="biome" value="Forest"/>
+              <property name="depth" type="int" value="3"/>
+              <property name="note">two
+lines</property>
+            </properties>
+            <tileset firstgid="1" name="inline" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+              <image source="sheet.png" width="32" height="32"/>
+              <tile id="2" type="solid"><properties><property name="tile_type" value="Stone"/></properties></tile>
+            </tileset>
+            <group name="things">
+              <objectgroup name="props">
+                <object id="7" name="oak" class="Tree" x="8" y="16" width="16" height="16" gid="3">
+                  <properties><property name="variant" type="int" value="1"/></properties>
+                </object>
+              </objectgroup>
+            </group>
+          </map>"#;
+        let (map, external) = parse_map(xml).unwrap();
+        assert!(external.is_empty());
+        assert_eq!(
+            map.properties["biome"],
+            Property::String("Forest".to_string())
+        );
+        assert_eq!(map.properties["depth"].as_int(), Some(3));
+        assert_eq!(map.properties["note"].as_str(), Some("two\nlines"));
+
+        let info = map.tile_info(3).unwrap();
+        assert_eq!(info.class, "solid");
+        assert_eq!(info.properties["tile_type"].as_str(), Some("Stone"));
+
+        let Layer::Objects(layer) = &map.layers[0] else {
+            panic!("expected the grouped object layer");
+        };
+        let object = &layer.objects[0];
+        assert_eq!((object.id, object.class.as_str()), (7, "Tree"));
+        assert_eq!(object.tile.map(|tile| tile.gid), Some(3));
+        assert_eq!(object.properties["variant"].as_int(), Some(1));
+    }
+}
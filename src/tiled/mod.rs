@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod export;
+pub mod import;
+pub mod loader;
+
+pub use components::{TiledMap, TiledTileset};
+
+// Loads maps & tilesets made in the Tiled editor as assets
+// import::map_to_chunks turns a loaded map into chunks for the world, export writes generated chunks back out
+pub struct TiledPlugin;
+
+impl Plugin for TiledPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TiledMap>();
+        app.init_asset::<TiledTileset>();
+        app.init_asset_loader::<loader::TmxLoader>();
+        app.init_asset_loader::<loader::TsxLoader>();
+    }
+}
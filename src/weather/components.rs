@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::procedural_generation::chunk::BiomeType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WeatherKind {
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+    Sandstorm,
+    Fog,
+}
+
+impl WeatherKind {
+    // Chance of each kind of weather in a biome, whatever is left over is clear
+    pub fn chances(biome: BiomeType) -> &'static [(WeatherKind, f32)] {
+        match biome {
+            BiomeType::Tundra | BiomeType::Mountain => {
+                &[(WeatherKind::Snow, 0.5), (WeatherKind::Fog, 0.15)]
+            }
+            BiomeType::Desert => &[(WeatherKind::Sandstorm, 0.35)],
+            BiomeType::Forest => &[(WeatherKind::Rain, 0.45), (WeatherKind::Fog, 0.15)],
+            BiomeType::Ocean => &[(WeatherKind::Rain, 0.3), (WeatherKind::Fog, 0.2)],
+            BiomeType::Plains => &[(WeatherKind::Rain, 0.2)],
+            BiomeType::Derendered => &[],
+        }
+    }
+
+    // Player speed multiplier at full intensity
+    fn movement(&self) -> f32 {
+        match self {
+            WeatherKind::Snow => 0.85,
+            WeatherKind::Sandstorm => 0.75,
+            WeatherKind::Rain => 0.95,
+            WeatherKind::Clear | WeatherKind::Fog => 1.0,
+        }
+    }
+
+    // How much can be seen at full intensity, 1.0 is everything
+    fn visibility(&self) -> f32 {
+        match self {
+            WeatherKind::Fog => 0.35,
+            WeatherKind::Sandstorm => 0.55,
+            WeatherKind::Rain => 0.85,
+            WeatherKind::Snow => 0.8,
+            WeatherKind::Clear => 1.0,
+        }
+    }
+}
+
+// The weather where the player is standing
+#[derive(Resource)]
+pub struct Weather {
+    pub current: WeatherKind,
+    // 0.0 - 1.0, fades out before the weather changes & back in afterwards
+    pub intensity: f32,
+    // Weather the current weather is heading towards
+    pub target: WeatherKind,
+    // Weather in each biome, re-rolled every time the timer finishes
+    pub regional: HashMap<BiomeType, WeatherKind>,
+    pub roll_timer: Timer,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            current: WeatherKind::Clear,
+            intensity: 0.0,
+            target: WeatherKind::Clear,
+            regional: HashMap::new(),
+            roll_timer: Timer::from_seconds(45.0, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Weather {
+    pub fn movement_multiplier(&self) -> f32 {
+        1.0 - (1.0 - self.current.movement()) * self.intensity
+    }
+
+    pub fn visibility(&self) -> f32 {
+        1.0 - (1.0 - self.current.visibility()) * self.intensity
+    }
+}
+
+#[derive(Component)]
+pub struct WeatherParticle {
+    // Offset from the camera, wrapped to stay on screen
+    pub offset: Vec2,
+    // Randomises speed a little so particles don't move in lockstep
+    pub speed: f32,
+}
+
+#[derive(Component)]
+pub struct FogOverlay;
@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+pub mod components;
+pub mod systems;
+
+use crate::states::AppState::*;
+
+pub use components::{Weather, WeatherKind};
+
+// Weather picked per biome region, with particles that follow the camera
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>();
+        app.add_systems(
+            OnEnter(InGame),
+            (systems::spawn_particles, systems::spawn_fog),
+        );
+        app.add_systems(
+            OnExit(InGame),
+            (systems::despawn_particles, systems::despawn_fog),
+        );
+        app.add_systems(
+            Update,
+            (
+                systems::roll_regional_weather,
+                systems::follow_local_weather,
+                systems::transition_weather,
+                systems::move_particles,
+                systems::update_fog,
+            )
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+    }
+}
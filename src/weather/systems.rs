@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::{thread_rng, Rng};
+
+use crate::camera::Camera;
+use crate::depth::Layer;
+use crate::player::components::Player;
+use crate::procedural_generation::chunk::{BiomeType, BIOMES};
+use crate::procedural_generation::map::{chunk_at, RenderedChunks};
+use crate::weather::components::*;
+
+const PARTICLES: usize = 160;
+// Seconds to fade the old weather out, and the new weather in
+const FADE_TIME: f32 = 6.0;
+const PARTICLE_Z: f32 = Layer::Overhead.z() + 0.5;
+
+pub fn roll_weather(biome: BiomeType, roll: f32) -> WeatherKind {
+    let mut total = 0.0;
+    for (kind, chance) in WeatherKind::chances(biome) {
+        total += chance;
+        if roll < total {
+            return *kind;
+        }
+    }
+    WeatherKind::Clear
+}
+
+pub fn roll_regional_weather(mut weather: ResMut<Weather>, time: Res<Time>) {
+    let finished = weather.roll_timer.tick(time.delta()).just_finished();
+    if !finished && !weather.regional.is_empty() {
+        return;
+    }
+    let mut rng = thread_rng();
+    for biome in BIOMES {
+        weather
+            .regional
+            .insert(biome, roll_weather(biome, rng.gen()));
+    }
+}
+
+pub fn follow_local_weather(
+    mut weather: ResMut<Weather>,
+    player_query: Query<&Transform, With<Player>>,
+    rendered: Res<RenderedChunks>,
+) {
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    let Some(chunk) = rendered
+        .chunks
+        .get(&chunk_at(transform.translation.truncate()))
+    else {
+        return;
+    };
+    let target = weather
+        .regional
+        .get(&chunk.biome)
+        .copied()
+        .unwrap_or_default();
+    if weather.target != target {
+        weather.target = target;
+    }
+}
+
+// Fades the current weather out, swaps it, then fades the new weather in
+pub fn transition_weather(mut weather: ResMut<Weather>, time: Res<Time>) {
+    let step = time.delta_seconds() / FADE_TIME;
+    if weather.current != weather.target {
+        weather.intensity -= step;
+        if weather.intensity <= 0.0 {
+            weather.intensity = 0.0;
+            weather.current = weather.target;
+        }
+    } else if weather.intensity < 1.0 {
+        weather.intensity = (weather.intensity + step).min(1.0);
+    }
+}
+
+// Direction, base speed, size & colour of each particle
+fn particle_style(kind: WeatherKind) -> Option<(Vec2, f32, Vec2, Color)> {
+    match kind {
+        WeatherKind::Rain => Some((
+            Vec2::new(-0.25, -1.0),
+            900.0,
+            Vec2::new(2.0, 16.0),
+            Color::srgba(0.6, 0.7, 1.0, 0.6),
+        )),
+        WeatherKind::Snow => Some((
+            Vec2::new(0.3, -1.0),
+            90.0,
+            Vec2::splat(5.0),
+            Color::srgba(1.0, 1.0, 1.0, 0.9),
+        )),
+        WeatherKind::Sandstorm => Some((
+            Vec2::new(1.0, -0.1),
+            700.0,
+            Vec2::new(6.0, 2.0),
+            Color::srgba(0.85, 0.7, 0.45, 0.7),
+        )),
+        WeatherKind::Clear | WeatherKind::Fog => None,
+    }
+}
+
+pub fn spawn_particles(mut commands: Commands) {
+    let mut rng = thread_rng();
+    for _ in 0..PARTICLES {
+        commands.spawn((
+            SpriteBundle {
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            WeatherParticle {
+                offset: Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)),
+                speed: rng.gen_range(0.7..1.3),
+            },
+        ));
+    }
+}
+
+pub fn despawn_particles(mut commands: Commands, query: Query<Entity, With<WeatherParticle>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// Particle offsets are stored as fractions of half the window so they wrap around the screen edges
+pub fn move_particles(
+    weather: Res<Weather>,
+    camera_query: Query<&Transform, (With<Camera>, Without<WeatherParticle>)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut particle_query: Query<(
+        &mut WeatherParticle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
+    time: Res<Time>,
+) {
+    let (Ok(camera), Ok(window)) = (camera_query.get_single(), window_query.get_single()) else {
+        return;
+    };
+    let half_screen = Vec2::new(window.width(), window.height()) / 2.0;
+
+    let Some((direction, base_speed, size, color)) = particle_style(weather.current) else {
+        for (_, _, _, mut visibility) in particle_query.iter_mut() {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+        }
+        return;
+    };
+    let color = color.with_alpha(color.alpha() * weather.intensity);
+
+    for (mut particle, mut transform, mut sprite, mut visibility) in particle_query.iter_mut() {
+        let velocity = direction.normalize() * base_speed * particle.speed / half_screen;
+        particle.offset += velocity * time.delta_seconds();
+        // Wrap to the other side of the screen
+        particle.offset.x = (particle.offset.x + 1.0).rem_euclid(2.0) - 1.0;
+        particle.offset.y = (particle.offset.y + 1.0).rem_euclid(2.0) - 1.0;
+
+        let position = camera.translation.truncate() + particle.offset * half_screen;
+        transform.translation = position.extend(PARTICLE_Z);
+        transform.rotation =
+            Quat::from_rotation_z(direction.to_angle() - std::f32::consts::FRAC_PI_2);
+        sprite.custom_size = Some(size);
+        sprite.color = color;
+        *visibility = Visibility::Visible;
+    }
+}
+
+pub fn spawn_fog(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+            background_color: Color::NONE.into(),
+            // Just above the day/night tint, below the HUD
+            z_index: ZIndex::Global(-9),
+            ..Default::default()
+        },
+        FogOverlay,
+    ));
+}
+
+pub fn despawn_fog(mut commands: Commands, query: Query<Entity, With<FogOverlay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Low visibility is shown as a haze over the world
+pub fn update_fog(weather: Res<Weather>, mut query: Query<&mut BackgroundColor, With<FogOverlay>>) {
+    let haze = match weather.current {
+        WeatherKind::Sandstorm => Color::srgb(0.8, 0.65, 0.4),
+        _ => Color::srgb(0.75, 0.78, 0.82),
+    };
+    let color = haze.with_alpha(1.0 - weather.visibility());
+    for mut background in query.iter_mut() {
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Tiles within this many tiles of the player are explored as they walk
+pub const EXPLORE_RADIUS: i32 = 6;
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapScreen {
+    #[default]
+    Closed,
+    Open,
+}
+
+// Everything the player has seen, saved with the game
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Exploration {
+    // Global tile coords
+    pub explored: HashSet<(i32, i32)>,
+    // Player placed markers, in global tile coords
+    pub markers: Vec<(i32, i32)>,
+    // Tiles explored since the map image was last painted
+    #[serde(skip)]
+    pub unpainted: Vec<(i32, i32)>,
+}
+
+#[derive(Resource)]
+pub struct WorldMapImage(pub Handle<Image>);
+
+// Zoom is screen pixels per tile, pan is an offset from the centre of the screen
+#[derive(Resource)]
+pub struct WorldMapView {
+    pub zoom: f32,
+    pub pan: Vec2,
+}
+
+impl Default for WorldMapView {
+    fn default() -> Self {
+        Self {
+            zoom: 6.0,
+            pan: Vec2::ZERO,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct WorldMapScreen;
+
+#[derive(Component)]
+pub struct WorldMapCanvas;
+
+#[derive(Component)]
+pub struct WorldMapMarker;
+
+#[derive(Component)]
+pub struct WorldMapPlayer;
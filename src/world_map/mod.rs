@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+mod components;
+pub mod systems;
+
+use crate::saves::SaveSet;
+use crate::states::AppState::*;
+
+pub use components::{Exploration, MapScreen};
+
+// Full-screen map of everything the player has explored, toggled with M
+pub struct WorldMapPlugin;
+
+impl Plugin for WorldMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<MapScreen>();
+        app.init_resource::<components::WorldMapView>();
+        app.add_systems(
+            Startup,
+            (
+                systems::exploration::load_exploration,
+                systems::screen::create_world_map_image,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                systems::exploration::explore_around_player,
+                systems::exploration::repaint_changed,
+                systems::exploration::paint_explored,
+                systems::screen::toggle_world_map,
+            )
+                .chain()
+                .run_if(in_state(InGame)),
+        );
+        app.add_systems(
+            Update,
+            (
+                systems::screen::zoom_and_pan,
+                systems::screen::place_markers,
+                systems::screen::layout_world_map,
+            )
+                .chain()
+                .run_if(in_state(MapScreen::Open)),
+        );
+        app.add_systems(OnEnter(MapScreen::Open), systems::screen::open_world_map);
+        app.add_systems(OnExit(MapScreen::Open), systems::screen::close_world_map);
+        app.add_systems(OnExit(InGame), systems::screen::force_close);
+        app.add_systems(
+            Last,
+            systems::exploration::save_exploration.in_set(SaveSet::Collect),
+        );
+    }
+}
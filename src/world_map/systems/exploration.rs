@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use crate::minimap::systems::tile_color;
+use crate::player::components::Player;
+use crate::procedural_generation::map::*;
+use crate::procedural_generation::tile_map::TileChanged;
+use crate::saves::SaveData;
+use crate::world_map::components::*;
+
+pub fn load_exploration(mut commands: Commands, save: Res<SaveData>) {
+    let mut exploration = save.get::<Exploration>("exploration").unwrap_or_default();
+    // Everything loaded from the save still has to be drawn
+    exploration.unpainted = exploration.explored.iter().copied().collect();
+    commands.insert_resource(exploration);
+}
+
+pub fn save_exploration(exploration: Res<Exploration>, mut save: ResMut<SaveData>) {
+    save.set("exploration", &*exploration);
+}
+
+// Explores a circle of tiles around the player whenever they step onto a new tile
+pub fn explore_around_player(
+    player_query: Query<&Transform, With<Player>>,
+    mut exploration: ResMut<Exploration>,
+    mut last_tile: Local<Option<IVec2>>,
+) {
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    let tile = tile_at(transform.translation.truncate());
+    if *last_tile == Some(tile) {
+        return;
+    }
+    *last_tile = Some(tile);
+
+    for dy in -EXPLORE_RADIUS..=EXPLORE_RADIUS {
+        for dx in -EXPLORE_RADIUS..=EXPLORE_RADIUS {
+            if dx * dx + dy * dy > EXPLORE_RADIUS * EXPLORE_RADIUS {
+                continue;
+            }
+            let explored = (tile.x + dx, tile.y + dy);
+            if exploration.explored.insert(explored) {
+                exploration.unpainted.push(explored);
+            }
+        }
+    }
+}
+
+// Edited tiles the player has already seen are drawn again
+pub fn repaint_changed(
+    mut changed: EventReader<TileChanged>,
+    mut exploration: ResMut<Exploration>,
+) {
+    for event in changed.read() {
+        let tile = (event.tile.x, event.tile.y);
+        if exploration.explored.contains(&tile) {
+            exploration.unpainted.push(tile);
+        }
+    }
+}
+
+pub fn paint_explored(
+    mut exploration: ResMut<Exploration>,
+    map_image: Res<WorldMapImage>,
+    mut images: ResMut<Assets<Image>>,
+    rendered: Res<RenderedChunks>,
+    derendered: Res<DerenderedChunks>,
+) {
+    if exploration.unpainted.is_empty() {
+        return;
+    }
+    let Some(image) = images.get_mut(&map_image.0) else {
+        return;
+    };
+    for (x, y) in exploration.unpainted.drain(..) {
+        if x < 0 || y < 0 || x >= WORLD_TILES as i32 || y >= WORLD_TILES as i32 {
+            continue;
+        }
+        let Some(tile) = find_tile(&rendered, &derendered, IVec2::new(x, y)) else {
+            continue;
+        };
+        let pixel = (WORLD_TILES - 1 - y as usize) * WORLD_TILES + x as usize;
+        image.data[pixel * 4..pixel * 4 + 4].copy_from_slice(&tile_color(&tile.tile_type));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod exploration;
+pub mod screen;
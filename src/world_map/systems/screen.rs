@@ -0,0 +1,255 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::window::PrimaryWindow;
+
+use crate::player::components::Player;
+use crate::procedural_generation::map::{tile_at, WORLD_TILES};
+use crate::world_map::components::*;
+
+// Unexplored tiles are left this colour
+const FOG: [u8; 4] = [12, 12, 18, 255];
+const MIN_ZOOM: f32 = 1.0;
+const MAX_ZOOM: f32 = 24.0;
+const MARKER_SIZE: f32 = 8.0;
+
+pub fn create_world_map_image(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: WORLD_TILES as u32,
+            height: WORLD_TILES as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &FOG,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    commands.insert_resource(WorldMapImage(images.add(image)));
+}
+
+pub fn toggle_world_map(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    current: Res<State<MapScreen>>,
+    mut next: ResMut<NextState<MapScreen>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        next.set(match current.get() {
+            MapScreen::Closed => MapScreen::Open,
+            MapScreen::Open => MapScreen::Closed,
+        });
+    }
+}
+
+// Leaving the game always closes the map
+pub fn force_close(mut next: ResMut<NextState<MapScreen>>) {
+    next.set(MapScreen::Closed);
+}
+
+// The game is paused while the map is open
+pub fn open_world_map(
+    mut commands: Commands,
+    map_image: Res<WorldMapImage>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    time.pause();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    overflow: Overflow::clip(),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.9).into(),
+                z_index: ZIndex::Global(10),
+                ..Default::default()
+            },
+            WorldMapScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..Default::default()
+                    },
+                    image: UiImage::new(map_image.0.clone()),
+                    ..Default::default()
+                },
+                WorldMapCanvas,
+            ));
+        });
+}
+
+pub fn close_world_map(
+    mut commands: Commands,
+    query: Query<Entity, With<WorldMapScreen>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    time.unpause();
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Scroll to zoom around the cursor, drag with the left mouse button to pan
+pub fn zoom_and_pan(
+    mut wheel: EventReader<MouseWheel>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut view: ResMut<WorldMapView>,
+    mut last_cursor: Local<Option<Vec2>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let cursor = window.cursor_position();
+    let centre = Vec2::new(window.width(), window.height()) / 2.0;
+
+    for event in wheel.read() {
+        let steps = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 40.0,
+        };
+        let zoom = (view.zoom * 1.15_f32.powf(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+        // Keep the point under the cursor in place
+        if let Some(cursor) = cursor {
+            let anchor = cursor - centre;
+            view.pan = anchor - (anchor - view.pan) * (zoom / view.zoom);
+        }
+        view.zoom = zoom;
+    }
+
+    if mouse_input.pressed(MouseButton::Left) {
+        if let (Some(cursor), Some(last)) = (cursor, *last_cursor) {
+            if cursor != last {
+                view.pan += cursor - last;
+            }
+        }
+    }
+    *last_cursor = cursor;
+}
+
+// Screen position of the top left corner of the map image
+fn canvas_origin(window: &Window, view: &WorldMapView) -> Vec2 {
+    let centre = Vec2::new(window.width(), window.height()) / 2.0;
+    centre + view.pan - Vec2::splat(WORLD_TILES as f32 * view.zoom) / 2.0
+}
+
+// Position inside the map image of the middle of a tile
+fn tile_to_canvas(tile: (i32, i32), zoom: f32) -> Vec2 {
+    Vec2::new(
+        (tile.0 as f32 + 0.5) * zoom,
+        (WORLD_TILES as f32 - 1.0 - tile.1 as f32 + 0.5) * zoom,
+    )
+}
+
+// Right click places a marker, or removes the one already on that tile
+pub fn place_markers(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    view: Res<WorldMapView>,
+    mut exploration: ResMut<Exploration>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let pixel = ((cursor - canvas_origin(window, &view)) / view.zoom).floor();
+    let tile = (pixel.x as i32, WORLD_TILES as i32 - 1 - pixel.y as i32);
+    if tile.0 < 0 || tile.1 < 0 || tile.0 >= WORLD_TILES as i32 || tile.1 >= WORLD_TILES as i32 {
+        return;
+    }
+
+    if let Some(index) = exploration
+        .markers
+        .iter()
+        .position(|marker| *marker == tile)
+    {
+        exploration.markers.remove(index);
+    } else {
+        exploration.markers.push(tile);
+    }
+}
+
+// Positions the map image and rebuilds the markers when the view or the markers change
+pub fn layout_world_map(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    view: Res<WorldMapView>,
+    exploration: Res<Exploration>,
+    player_query: Query<&Transform, With<Player>>,
+    mut canvas_query: Query<(Entity, &mut Style), With<WorldMapCanvas>>,
+    added: Query<(), Added<WorldMapCanvas>>,
+) {
+    if !view.is_changed() && !exploration.is_changed() && added.is_empty() {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let Ok((canvas, mut style)) = canvas_query.get_single_mut() else {
+        return;
+    };
+
+    let origin = canvas_origin(window, &view);
+    let size = WORLD_TILES as f32 * view.zoom;
+    style.left = Val::Px(origin.x);
+    style.top = Val::Px(origin.y);
+    style.width = Val::Px(size);
+    style.height = Val::Px(size);
+
+    let player_tile = player_query
+        .get_single()
+        .ok()
+        .map(|transform| tile_at(transform.translation.truncate()));
+
+    commands.entity(canvas).despawn_descendants();
+    commands.entity(canvas).with_children(|parent| {
+        for marker in exploration.markers.iter() {
+            spawn_dot(
+                parent,
+                tile_to_canvas(*marker, view.zoom),
+                Color::srgb(1.0, 0.85, 0.2),
+                WorldMapMarker,
+            );
+        }
+        if let Some(tile) = player_tile {
+            spawn_dot(
+                parent,
+                tile_to_canvas((tile.x, tile.y), view.zoom),
+                Color::srgb(1.0, 0.2, 0.2),
+                WorldMapPlayer,
+            );
+        }
+    });
+}
+
+fn spawn_dot(parent: &mut ChildBuilder, position: Vec2, color: Color, marker: impl Component) {
+    parent.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(position.x - MARKER_SIZE / 2.0),
+                top: Val::Px(position.y - MARKER_SIZE / 2.0),
+                width: Val::Px(MARKER_SIZE),
+                height: Val::Px(MARKER_SIZE),
+                ..Default::default()
+            },
+            background_color: color.into(),
+            ..Default::default()
+        },
+        marker,
+    ));
+}